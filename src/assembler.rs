@@ -0,0 +1,986 @@
+//! Assembles Octo source into a ROM that loads at 0x200.
+//!
+//! Supports the instructions the emulator runs, written the way Octo writes
+//! them, plus:
+//! - `: name` labels, which can be used before they're defined
+//! - `:const name value` and `:alias name vX`
+//! - `:macro name args { ... }`, expanded wherever `name args` appears
+//! - `:call`, `:byte` and `:org`
+//! - `if ... then`, `if ... begin ... else ... end`, `loop ... while ... again`
+//! - bare numbers, emitted as data bytes, e.g. `0b01111110` for a sprite row
+//!
+//! A bare name that isn't anything else is a call to the label of that name.
+//! As in Octo a program starts at `: main`, with a jump there at 0x200 if
+//! anything comes before it.
+//!
+//! ```
+//! let rom = chip8::assembler::assemble(
+//!     "
+//!     : main
+//!       v0 := 5
+//!       loop
+//!         v0 -= 1
+//!         if v0 != 0 then
+//!       again
+//!     ",
+//! )
+//! .unwrap();
+//! assert_eq!(rom, [0x60, 0x05, 0x70, 0xFF, 0x30, 0x00, 0x12, 0x02]);
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::{error::Error, fmt};
+
+use crate::PROGRAM_START;
+
+/// Guards against a macro that expands into itself forever.
+const MAX_MACRO_DEPTH: usize = 1_000;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AssembleError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for AssembleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for AssembleError {}
+
+#[derive(Clone, Debug)]
+struct Token {
+    text: String,
+    line: usize,
+    /// Marks where a macro's expansion ends, rather than source text.
+    ends_macro: bool,
+}
+
+struct Macro {
+    parameters: Vec<String>,
+    body: Vec<Token>,
+}
+
+enum FixupKind {
+    /// The low 12 bits of a two byte instruction.
+    Address,
+    /// A whole 16 bit word, for `i := long`.
+    Long,
+}
+
+/// A label used before it was defined, patched in once the source has been
+/// read.
+struct Fixup {
+    address: usize,
+    label: String,
+    kind: FixupKind,
+    line: usize,
+}
+
+#[derive(Clone, Copy)]
+enum Operand {
+    Register(u8),
+    Value(u8),
+}
+
+#[derive(Clone, Copy)]
+enum Condition {
+    Equal(u8, Operand),
+    NotEqual(u8, Operand),
+    Key(u8),
+    NotKey(u8),
+}
+
+impl Condition {
+    fn negate(self) -> Condition {
+        match self {
+            Condition::Equal(x, operand) => Condition::NotEqual(x, operand),
+            Condition::NotEqual(x, operand) => Condition::Equal(x, operand),
+            Condition::Key(x) => Condition::NotKey(x),
+            Condition::NotKey(x) => Condition::Key(x),
+        }
+    }
+
+    /// The instruction that skips the next one when the condition holds.
+    fn skip(self) -> u16 {
+        let x = |x: u8| (x as u16) << 8;
+        match self {
+            Condition::Equal(vx, Operand::Value(value)) => 0x3000 | x(vx) | value as u16,
+            Condition::NotEqual(vx, Operand::Value(value)) => 0x4000 | x(vx) | value as u16,
+            Condition::Equal(vx, Operand::Register(vy)) => 0x5000 | x(vx) | (vy as u16) << 4,
+            Condition::NotEqual(vx, Operand::Register(vy)) => 0x9000 | x(vx) | (vy as u16) << 4,
+            Condition::Key(vx) => 0xE09E | x(vx),
+            Condition::NotKey(vx) => 0xE0A1 | x(vx),
+        }
+    }
+}
+
+struct Loop {
+    start: usize,
+    /// `while` jumps out of the loop, patched by `again`.
+    breaks: Vec<usize>,
+    line: usize,
+}
+
+struct Block {
+    /// The jump past this part of the `if`, patched by `else` or `end`.
+    jump: usize,
+    line: usize,
+}
+
+struct Assembler {
+    tokens: VecDeque<Token>,
+    line: usize,
+    rom: Vec<u8>,
+    address: usize,
+    labels: HashMap<String, u16>,
+    constants: HashMap<String, i64>,
+    aliases: HashMap<String, u8>,
+    macros: HashMap<String, Macro>,
+    fixups: Vec<Fixup>,
+    loops: Vec<Loop>,
+    blocks: Vec<Block>,
+    // Macro expansions the assembler is inside
+    macro_depth: usize,
+}
+
+/// Assembles `source` into the bytes to load at 0x200.
+pub fn assemble(source: &str) -> Result<Vec<u8>, AssembleError> {
+    let tokens = source
+        .lines()
+        .enumerate()
+        .flat_map(|(number, line)| {
+            let code = line.split('#').next().unwrap_or_default();
+            code.split_whitespace().map(move |text| Token {
+                text: text.to_string(),
+                line: number + 1,
+                ends_macro: false,
+            })
+        })
+        .collect();
+    let mut assembler = Assembler {
+        tokens,
+        line: 1,
+        rom: Vec::new(),
+        address: PROGRAM_START as usize,
+        labels: HashMap::new(),
+        constants: HashMap::new(),
+        aliases: HashMap::new(),
+        macros: HashMap::new(),
+        fixups: Vec::new(),
+        loops: Vec::new(),
+        blocks: Vec::new(),
+        macro_depth: 0,
+    };
+    assembler.assemble()?;
+    Ok(assembler.rom)
+}
+
+fn parse_number(text: &str) -> Option<i64> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text),
+    };
+    let value = if let Some(hex) = digits.strip_prefix("0x") {
+        i64::from_str_radix(hex, 16).ok()?
+    } else if let Some(binary) = digits.strip_prefix("0b") {
+        i64::from_str_radix(binary, 2).ok()?
+    } else {
+        digits.parse().ok()?
+    };
+    Some(if negative { -value } else { value })
+}
+
+fn parse_register(text: &str) -> Option<u8> {
+    let digit = text.strip_prefix(['v', 'V'])?;
+    if digit.len() != 1 {
+        return None;
+    }
+    u8::from_str_radix(digit, 16).ok()
+}
+
+impl Assembler {
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, AssembleError> {
+        Err(AssembleError {
+            line: self.line,
+            message: message.into(),
+        })
+    }
+
+    fn next_token(&mut self) -> Option<Token> {
+        self.skip_macro_ends();
+        let token = self.tokens.pop_front()?;
+        self.line = token.line;
+        Some(token)
+    }
+
+    fn peek_text(&mut self) -> Option<&str> {
+        self.skip_macro_ends();
+        self.tokens.front().map(|token| token.text.as_str())
+    }
+
+    fn skip_macro_ends(&mut self) {
+        while self.tokens.front().is_some_and(|token| token.ends_macro) {
+            self.tokens.pop_front();
+            self.macro_depth -= 1;
+        }
+    }
+
+    fn expect(&mut self, what: &str) -> Result<Token, AssembleError> {
+        match self.next_token() {
+            Some(token) => Ok(token),
+            None => self.error(format!("expected {} at the end of the file", what)),
+        }
+    }
+
+    fn expect_text(&mut self, text: &str) -> Result<(), AssembleError> {
+        let token = self.expect(&format!("'{}'", text))?;
+        if token.text != text {
+            return self.error(format!("expected '{}', found '{}'", text, token.text));
+        }
+        Ok(())
+    }
+
+    fn assemble(&mut self) -> Result<(), AssembleError> {
+        // Jump to main, dropped again if main turns out to be right here
+        self.fixups.push(Fixup {
+            address: self.address,
+            label: "main".to_string(),
+            kind: FixupKind::Address,
+            line: 1,
+        });
+        self.emit_word(0x1000)?;
+
+        while let Some(token) = self.next_token() {
+            self.statement(token)?;
+        }
+
+        if let Some(open) = self.loops.last() {
+            self.line = open.line;
+            return self.error("'loop' without 'again'");
+        }
+        if let Some(open) = self.blocks.last() {
+            self.line = open.line;
+            return self.error("'begin' without 'end'");
+        }
+        for fixup in std::mem::take(&mut self.fixups) {
+            let Some(&target) = self.labels.get(&fixup.label) else {
+                self.line = fixup.line;
+                return self.error(format!("unknown label '{}'", fixup.label));
+            };
+            let offset = fixup.address - PROGRAM_START as usize;
+            match fixup.kind {
+                FixupKind::Address => {
+                    if target > 0xFFF {
+                        self.line = fixup.line;
+                        return self.error(format!(
+                            "label '{}' at {:04X} is out of reach, use 'i := long'",
+                            fixup.label, target
+                        ));
+                    }
+                    self.rom[offset] |= (target >> 8) as u8;
+                    self.rom[offset + 1] |= target as u8;
+                }
+                FixupKind::Long => {
+                    self.rom[offset..offset + 2].copy_from_slice(&target.to_be_bytes());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self, token: Token) -> Result<(), AssembleError> {
+        match token.text.as_str() {
+            ":" => {
+                let name = self.expect("a label name")?.text;
+                self.define_label(name)?;
+            }
+            ":const" => {
+                let name = self.expect("a constant name")?.text;
+                let token = self.expect("a value")?;
+                let value = self.value(&token)?;
+                self.constants.insert(name, value);
+            }
+            ":alias" => {
+                let name = self.expect("an alias name")?.text;
+                let token = self.expect("a register")?;
+                let register = self.register(&token)?;
+                self.aliases.insert(name, register);
+            }
+            ":macro" => self.define_macro()?,
+            ":call" => {
+                let token = self.expect("an address")?;
+                self.emit_address(0x2000, &token)?;
+            }
+            ":byte" => {
+                let token = self.expect("a byte")?;
+                let byte = self.byte(&token)?;
+                self.emit_byte(byte)?;
+            }
+            ":org" => {
+                let token = self.expect("an address")?;
+                let address = self.value(&token)?;
+                if !(PROGRAM_START as i64..=u16::MAX as i64).contains(&address) {
+                    return self.error(format!("can't assemble at address {}", token.text));
+                }
+                self.address = address as usize;
+            }
+            "clear" => self.emit_word(0x00E0)?,
+            "return" | ";" => self.emit_word(0x00EE)?,
+            "scroll-right" => self.emit_word(0x00FB)?,
+            "scroll-left" => self.emit_word(0x00FC)?,
+            "exit" => self.emit_word(0x00FD)?,
+            "lores" => self.emit_word(0x00FE)?,
+            "hires" => self.emit_word(0x00FF)?,
+            "audio" => self.emit_word(0xF002)?,
+            "scroll-down" => {
+                let rows = self.nibble()?;
+                self.emit_word(0x00C0 | rows)?;
+            }
+            "plane" => {
+                let planes = self.nibble()?;
+                self.emit_word(0xF001 | planes << 8)?;
+            }
+            "jump" => {
+                let token = self.expect("an address")?;
+                self.emit_address(0x1000, &token)?;
+            }
+            "jump0" => {
+                let token = self.expect("an address")?;
+                self.emit_address(0xB000, &token)?;
+            }
+            "sprite" => {
+                let x = self.next_register()?;
+                let y = self.next_register()?;
+                let rows = self.nibble()?;
+                self.emit_word(0xD000 | x << 8 | y << 4 | rows)?;
+            }
+            "bcd" => self.register_instruction(0xF033)?,
+            "saveflags" => self.register_instruction(0xF075)?,
+            "loadflags" => self.register_instruction(0xF085)?,
+            "save" => self.register_range(0xF055, 0x5002)?,
+            "load" => self.register_range(0xF065, 0x5003)?,
+            "delay" => self.timer_assignment(0xF015)?,
+            "buzzer" => self.timer_assignment(0xF018)?,
+            "pitch" => self.timer_assignment(0xF03A)?,
+            "i" => self.index_statement()?,
+            "if" => {
+                let condition = self.condition()?;
+                let token = self.expect("'then' or 'begin'")?;
+                match token.text.as_str() {
+                    "then" => self.emit_word(condition.negate().skip())?,
+                    "begin" => {
+                        self.emit_word(condition.skip())?;
+                        let jump = self.address;
+                        self.emit_word(0x1000)?;
+                        self.blocks.push(Block {
+                            jump,
+                            line: self.line,
+                        });
+                    }
+                    other => {
+                        return self.error(format!("expected 'then' or 'begin', found '{}'", other))
+                    }
+                }
+            }
+            "else" => {
+                let Some(block) = self.blocks.pop() else {
+                    return self.error("'else' without 'begin'");
+                };
+                let jump = self.address;
+                self.emit_word(0x1000)?;
+                self.patch_jump(block.jump, self.address)?;
+                self.blocks.push(Block {
+                    jump,
+                    line: block.line,
+                });
+            }
+            "end" => {
+                let Some(block) = self.blocks.pop() else {
+                    return self.error("'end' without 'begin'");
+                };
+                self.patch_jump(block.jump, self.address)?;
+            }
+            "loop" => self.loops.push(Loop {
+                start: self.address,
+                breaks: Vec::new(),
+                line: self.line,
+            }),
+            "while" => {
+                let condition = self.condition()?;
+                let Some(open) = self.loops.last_mut() else {
+                    return self.error("'while' outside a loop");
+                };
+                open.breaks.push(self.address + 2);
+                self.emit_word(condition.skip())?;
+                self.emit_word(0x1000)?;
+            }
+            "again" => {
+                let Some(open) = self.loops.pop() else {
+                    return self.error("'again' without 'loop'");
+                };
+                self.emit_jump(open.start)?;
+                for jump in open.breaks {
+                    self.patch_jump(jump, self.address)?;
+                }
+            }
+            text if self.macros.contains_key(text) => self.expand_macro(&token)?,
+            text if self.register_name(text).is_some() => self.register_statement(&token)?,
+            text if parse_number(text).is_some() || self.constants.contains_key(text) => {
+                let byte = self.byte(&token)?;
+                self.emit_byte(byte)?;
+            }
+            text if text.starts_with(':') || text == "{" || text == "}" => {
+                return self.error(format!("unexpected '{}'", text));
+            }
+            _ => self.emit_address(0x2000, &token)?,
+        }
+        Ok(())
+    }
+
+    fn define_label(&mut self, name: String) -> Result<(), AssembleError> {
+        if self.labels.contains_key(&name) {
+            return self.error(format!("label '{}' is defined twice", name));
+        }
+        if parse_number(&name).is_some() || self.register_name(&name).is_some() {
+            return self.error(format!("'{}' can't be used as a label", name));
+        }
+        if name == "main" && self.address == PROGRAM_START as usize + 2 && self.rom.len() == 2 {
+            // Nothing but the jump to main so far, so main can start at 0x200
+            self.rom.clear();
+            self.fixups
+                .retain(|fixup| fixup.address != PROGRAM_START as usize);
+            self.address = PROGRAM_START as usize;
+            for address in self.labels.values_mut() {
+                *address = PROGRAM_START;
+            }
+        }
+        self.labels.insert(name, self.address as u16);
+        Ok(())
+    }
+
+    fn define_macro(&mut self) -> Result<(), AssembleError> {
+        let name = self.expect("a macro name")?.text;
+        let mut parameters = Vec::new();
+        loop {
+            let token = self.expect("'{'")?;
+            if token.text == "{" {
+                break;
+            }
+            parameters.push(token.text);
+        }
+        let mut body = Vec::new();
+        let mut depth = 1;
+        loop {
+            let token = self.expect("'}'")?;
+            match token.text.as_str() {
+                "{" => depth += 1,
+                "}" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+            body.push(token);
+        }
+        self.macros.insert(name, Macro { parameters, body });
+        Ok(())
+    }
+
+    fn expand_macro(&mut self, call: &Token) -> Result<(), AssembleError> {
+        let definition = &self.macros[&call.text];
+        let (parameters, body) = (definition.parameters.clone(), definition.body.clone());
+        let mut arguments = HashMap::new();
+        for parameter in parameters.iter() {
+            let Some(argument) = self.next_token() else {
+                return self.error(format!(
+                    "macro '{}' needs {} arguments",
+                    call.text,
+                    parameters.len()
+                ));
+            };
+            arguments.insert(parameter.clone(), argument.text);
+        }
+
+        // Counted after the arguments, which may finish the expansion the
+        // call came from
+        self.macro_depth += 1;
+        if self.macro_depth > MAX_MACRO_DEPTH {
+            return self.error(format!("macros nested too deeply in '{}'", call.text));
+        }
+        self.tokens.push_front(Token {
+            text: String::new(),
+            line: call.line,
+            ends_macro: true,
+        });
+        for token in body.into_iter().rev() {
+            self.tokens.push_front(Token {
+                text: arguments.get(&token.text).cloned().unwrap_or(token.text),
+                line: call.line,
+                ends_macro: false,
+            });
+        }
+        Ok(())
+    }
+
+    fn register_statement(&mut self, target: &Token) -> Result<(), AssembleError> {
+        let x = self.register(target)?;
+        let operator = self.expect("an operator")?.text;
+        let token = self.expect("a value")?;
+        let vx = (x as u16) << 8;
+        let opcode = match (operator.as_str(), self.register_name(&token.text)) {
+            (":=", Some(y)) => 0x8000 | vx | (y as u16) << 4,
+            ("|=", Some(y)) => 0x8001 | vx | (y as u16) << 4,
+            ("&=", Some(y)) => 0x8002 | vx | (y as u16) << 4,
+            ("^=", Some(y)) => 0x8003 | vx | (y as u16) << 4,
+            ("+=", Some(y)) => 0x8004 | vx | (y as u16) << 4,
+            ("-=", Some(y)) => 0x8005 | vx | (y as u16) << 4,
+            (">>=", Some(y)) => 0x8006 | vx | (y as u16) << 4,
+            ("=-", Some(y)) => 0x8007 | vx | (y as u16) << 4,
+            ("<<=", Some(y)) => 0x800E | vx | (y as u16) << 4,
+            (":=", None) => match token.text.as_str() {
+                "random" => {
+                    let token = self.expect("a mask")?;
+                    0xC000 | vx | self.byte(&token)? as u16
+                }
+                "delay" => 0xF007 | vx,
+                "key" => 0xF00A | vx,
+                _ => 0x6000 | vx | self.byte(&token)? as u16,
+            },
+            ("+=", None) => 0x7000 | vx | self.byte(&token)? as u16,
+            ("-=", None) => 0x7000 | vx | self.byte(&token)?.wrapping_neg() as u16,
+            _ => {
+                return self.error(format!(
+                    "can't assemble '{} {} {}'",
+                    target.text, operator, token.text
+                ))
+            }
+        };
+        self.emit_word(opcode)
+    }
+
+    fn index_statement(&mut self) -> Result<(), AssembleError> {
+        let operator = self.expect("an operator")?.text;
+        match operator.as_str() {
+            ":=" => {
+                let token = self.expect("an address")?;
+                match token.text.as_str() {
+                    "hex" => self.register_instruction(0xF029),
+                    "bighex" => self.register_instruction(0xF030),
+                    "long" => {
+                        let token = self.expect("an address")?;
+                        self.emit_word(0xF000)?;
+                        self.emit_long_address(&token)
+                    }
+                    _ => self.emit_address(0xA000, &token),
+                }
+            }
+            "+=" => self.register_instruction(0xF01E),
+            _ => self.error(format!("can't assemble 'i {}'", operator)),
+        }
+    }
+
+    fn timer_assignment(&mut self, opcode: u16) -> Result<(), AssembleError> {
+        self.expect_text(":=")?;
+        self.register_instruction(opcode)
+    }
+
+    /// `save vX` and `load vX`, or their XO-CHIP `vX - vY` range forms.
+    fn register_range(&mut self, opcode: u16, range_opcode: u16) -> Result<(), AssembleError> {
+        let x = self.next_register()?;
+        if self.peek_text() != Some("-") {
+            return self.emit_word(opcode | x << 8);
+        }
+        self.next_token();
+        let y = self.next_register()?;
+        self.emit_word(range_opcode | x << 8 | y << 4)
+    }
+
+    fn register_instruction(&mut self, opcode: u16) -> Result<(), AssembleError> {
+        let x = self.next_register()?;
+        self.emit_word(opcode | x << 8)
+    }
+
+    fn condition(&mut self) -> Result<Condition, AssembleError> {
+        let token = self.expect("a register")?;
+        let x = self.register(&token)?;
+        let operator = self.expect("a comparison")?.text;
+        match operator.as_str() {
+            "key" => return Ok(Condition::Key(x)),
+            "-key" => return Ok(Condition::NotKey(x)),
+            _ => {}
+        }
+        let token = self.expect("a value")?;
+        let operand = match self.register_name(&token.text) {
+            Some(y) => Operand::Register(y),
+            None => Operand::Value(self.byte(&token)?),
+        };
+        match operator.as_str() {
+            "==" => Ok(Condition::Equal(x, operand)),
+            "!=" => Ok(Condition::NotEqual(x, operand)),
+            _ => self.error(format!("unknown comparison '{}'", operator)),
+        }
+    }
+
+    fn register_name(&self, text: &str) -> Option<u8> {
+        parse_register(text).or_else(|| self.aliases.get(text).copied())
+    }
+
+    fn register(&self, token: &Token) -> Result<u8, AssembleError> {
+        match self.register_name(&token.text) {
+            Some(register) => Ok(register),
+            None => self.error(format!("expected a register, found '{}'", token.text)),
+        }
+    }
+
+    fn next_register(&mut self) -> Result<u16, AssembleError> {
+        let token = self.expect("a register")?;
+        Ok(self.register(&token)? as u16)
+    }
+
+    fn value(&self, token: &Token) -> Result<i64, AssembleError> {
+        match parse_number(&token.text).or_else(|| self.constants.get(&token.text).copied()) {
+            Some(value) => Ok(value),
+            None => self.error(format!("expected a number, found '{}'", token.text)),
+        }
+    }
+
+    fn ranged_value(
+        &self,
+        token: &Token,
+        range: std::ops::RangeInclusive<i64>,
+    ) -> Result<i64, AssembleError> {
+        let value = self.value(token)?;
+        if !range.contains(&value) {
+            return self.error(format!("{} is out of range", token.text));
+        }
+        Ok(value)
+    }
+
+    fn byte(&self, token: &Token) -> Result<u8, AssembleError> {
+        Ok(self.ranged_value(token, -128..=255)? as u8)
+    }
+
+    fn nibble(&mut self) -> Result<u16, AssembleError> {
+        let token = self.expect("a number")?;
+        Ok(self.ranged_value(&token, 0..=15)? as u16)
+    }
+
+    fn emit_byte(&mut self, byte: u8) -> Result<(), AssembleError> {
+        if self.address > u16::MAX as usize {
+            return self.error("program runs past the end of memory");
+        }
+        let offset = self.address - PROGRAM_START as usize;
+        if offset >= self.rom.len() {
+            self.rom.resize(offset + 1, 0);
+        }
+        self.rom[offset] = byte;
+        self.address += 1;
+        Ok(())
+    }
+
+    fn emit_word(&mut self, word: u16) -> Result<(), AssembleError> {
+        for byte in word.to_be_bytes() {
+            self.emit_byte(byte)?;
+        }
+        Ok(())
+    }
+
+    fn emit_jump(&mut self, target: usize) -> Result<(), AssembleError> {
+        if target > 0xFFF {
+            return self.error(format!("{:04X} is out of reach of a jump", target));
+        }
+        self.emit_word(0x1000 | target as u16)
+    }
+
+    fn patch_jump(&mut self, jump: usize, target: usize) -> Result<(), AssembleError> {
+        if target > 0xFFF {
+            return self.error(format!("{:04X} is out of reach of a jump", target));
+        }
+        let offset = jump - PROGRAM_START as usize;
+        self.rom[offset..offset + 2].copy_from_slice(&(0x1000 | target as u16).to_be_bytes());
+        Ok(())
+    }
+
+    /// Emits an instruction with a 12 bit address, given as a number or a
+    /// label.
+    fn emit_address(&mut self, opcode: u16, token: &Token) -> Result<(), AssembleError> {
+        if parse_number(&token.text).is_some() || self.constants.contains_key(&token.text) {
+            let address = self.ranged_value(token, 0..=0xFFF)?;
+            return self.emit_word(opcode | address as u16);
+        }
+        self.fixups.push(Fixup {
+            address: self.address,
+            label: token.text.clone(),
+            kind: FixupKind::Address,
+            line: self.line,
+        });
+        self.emit_word(opcode)
+    }
+
+    fn emit_long_address(&mut self, token: &Token) -> Result<(), AssembleError> {
+        if parse_number(&token.text).is_some() || self.constants.contains_key(&token.text) {
+            let address = self.ranged_value(token, 0..=u16::MAX as i64)?;
+            return self.emit_word(address as u16);
+        }
+        self.fixups.push(Fixup {
+            address: self.address,
+            label: token.text.clone(),
+            kind: FixupKind::Long,
+            line: self.line,
+        });
+        self.emit_word(0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::assembler::{assemble, AssembleError};
+    use crate::disassembler::disassemble;
+    use crate::run_state::{HaltReason, RunState};
+    use crate::Chip8;
+
+    fn error(source: &str) -> String {
+        match assemble(source) {
+            Ok(rom) => panic!("expected an error, assembled {:02X?}", rom),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_assemble_instructions() {
+        let rom = assemble(
+            "
+            : main
+              clear
+              v3 := 0x69
+              v5 += 0x53
+              v7 := vA
+              vF <<= v8
+              v3 =- v6
+              v2 := random 0x3F
+              v4 := delay
+              v9 := key
+              delay := v5
+              buzzer := v6
+              i := 0xDFF
+              i += v1
+              i := hex vA
+              i := bighex v4
+              sprite v3 v2 8
+              bcd v3
+              save v6
+              load v1
+              save v1 - v4
+              saveflags v7
+              scroll-down 7
+              plane 3
+              pitch := v6
+              return
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(
+            rom,
+            [
+                0x00, 0xE0, 0x63, 0x69, 0x75, 0x53, 0x87, 0xA0, 0x8F, 0x8E, 0x83, 0x67, 0xC2, 0x3F,
+                0xF4, 0x07, 0xF9, 0x0A, 0xF5, 0x15, 0xF6, 0x18, 0xAD, 0xFF, 0xF1, 0x1E, 0xFA, 0x29,
+                0xF4, 0x30, 0xD3, 0x28, 0xF3, 0x33, 0xF6, 0x55, 0xF1, 0x65, 0x51, 0x42, 0xF7, 0x75,
+                0x00, 0xC7, 0xF3, 0x01, 0xF6, 0x3A, 0x00, 0xEE,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_assemble_labels_and_sprite_data() {
+        let rom = assemble(
+            "
+            : main
+              i := face
+              draw-face       # a forward call
+              jump main
+            : draw-face
+              sprite v0 v1 2
+              ;
+            : face
+              0b00111100 0x42
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(
+            rom,
+            [0xA2, 0x0A, 0x22, 0x06, 0x12, 0x00, 0xD0, 0x12, 0x00, 0xEE, 0x3C, 0x42]
+        );
+    }
+
+    #[test]
+    fn test_assemble_jumps_to_main_after_data() {
+        let rom = assemble(
+            "
+            : data 1 2
+            : main
+              jump main
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(rom, [0x12, 0x04, 0x01, 0x02, 0x12, 0x04]);
+    }
+
+    #[test]
+    fn test_assemble_const_alias_and_macro() {
+        let rom = assemble(
+            "
+            :const SPEED 3
+            :alias x v4
+            :macro move register amount { register += amount }
+            : main
+              x := SPEED
+              move x SPEED
+              move v0 -1
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(rom, [0x64, 0x03, 0x74, 0x03, 0x70, 0xFF]);
+    }
+
+    #[test]
+    fn test_assemble_macro_limit_counts_depth() {
+        // Far more expansions than the depth limit, one after another
+        let source = format!(
+            ":macro nothing {{ }}\n:macro set value {{ v0 := value }}\n\
+             :macro wrapped {{ set }}\n: main\n{}\nwrapped 7",
+            "nothing ".repeat(20_000)
+        );
+
+        assert_eq!(assemble(&source).unwrap(), [0x60, 0x07]);
+    }
+
+    #[test]
+    fn test_assemble_control_flow() {
+        let rom = assemble(
+            "
+            : main
+              loop
+                if v0 == 5 begin
+                  v1 := 1
+                else
+                  v1 := 2
+                end
+                while v2 != v3
+                if v4 key then v5 -= 1
+              again
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(
+            rom,
+            [
+                0x30, 0x05, // skip the jump to else when v0 == 5
+                0x12, 0x08, //
+                0x61, 0x01, //
+                0x12, 0x0A, // jump past else
+                0x61, 0x02, //
+                0x92, 0x30, // while: skip the break when v2 != v3
+                0x12, 0x14, //
+                0xE4, 0xA1, // if v4 key then
+                0x75, 0xFF, //
+                0x12, 0x00, // again
+            ]
+        );
+    }
+
+    #[test]
+    fn test_assemble_long_index_and_org() {
+        let rom = assemble(
+            "
+            : main
+              i := long far
+              exit
+            :org 0x210
+            : far
+              0xAB
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(rom.len(), 0x11);
+        assert_eq!(rom[..6], [0xF0, 0x00, 0x02, 0x10, 0x00, 0xFD]);
+        assert_eq!(rom[0x10], 0xAB);
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        assert_eq!(
+            assemble(": main\n  jump nowhere"),
+            Err(AssembleError {
+                line: 2,
+                message: "unknown label 'nowhere'".to_string()
+            })
+        );
+        assert_eq!(error("v0 := 300"), "line 1: 300 is out of range");
+        assert_eq!(
+            error(": main\n: main"),
+            "line 2: label 'main' is defined twice"
+        );
+        assert_eq!(error(": main\nloop\n"), "line 2: 'loop' without 'again'");
+        assert_eq!(
+            error(": main\nsprite v0 v1"),
+            "line 2: expected a number at the end of the file"
+        );
+        assert_eq!(
+            error(": main v0 += i"),
+            "line 1: expected a number, found 'i'"
+        );
+        assert_eq!(error("v0 := 1"), "line 1: unknown label 'main'");
+        assert_eq!(
+            error(":macro forever { forever } : main forever"),
+            "line 1: macros nested too deeply in 'forever'"
+        );
+    }
+
+    #[test]
+    fn test_disassembly_assembles_back() {
+        let program = [
+            0x00, 0xE0, 0x60, 0x00, 0x61, 0x00, 0xA2, 0x14, 0xD0, 0x15, 0x70, 0x08, 0x30, 0x20,
+            0x12, 0x08, 0xF0, 0x0A, 0x12, 0x00, 0xF0, 0x90, 0xF0, 0x90, 0x90, 0x2A,
+        ];
+
+        let rom = assemble(&disassemble(&program, false)).unwrap();
+
+        assert_eq!(rom, program);
+    }
+
+    #[test]
+    fn test_assembled_program_runs() {
+        let rom = assemble(
+            "
+            : main
+              v0 := 0
+              loop
+                v0 += 1
+                if v0 != 10 then
+              again
+              exit
+            ",
+        )
+        .unwrap();
+        let mut emulator = Chip8::new();
+        emulator.load_program(&rom).unwrap();
+
+        for _ in 0..10 {
+            emulator.run_frame().unwrap();
+        }
+
+        assert_eq!(emulator.registers()[0], 10);
+        assert_eq!(emulator.run_state(), RunState::Halted(HaltReason::Exit));
+    }
+}
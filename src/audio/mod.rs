@@ -0,0 +1,175 @@
+//! Sound. XO-CHIP plays a 128 bit pattern, one bit at a time, at a rate set
+//! by the pitch register while the sound timer is running. Other platforms
+//! just beep, which is played here as a fixed square wave pattern.
+
+use std::io;
+
+pub mod wav;
+
+pub const PATTERN_SIZE: usize = 16;
+const PATTERN_BITS: f64 = (PATTERN_SIZE * 8) as f64;
+
+/// A 500 Hz square wave at the default pitch, used until F002 loads a
+/// pattern of the program's own.
+pub const DEFAULT_PATTERN: [u8; PATTERN_SIZE] = [0xF0; PATTERN_SIZE];
+/// The pitch that plays the pattern at 4000 bits per second.
+pub const DEFAULT_PITCH: u8 = 64;
+pub const DEFAULT_SAMPLE_RATE: u32 = 44100;
+
+const AMPLITUDE: i16 = i16::MAX / 4;
+
+/// Sound output, driven by the sound timer. The emulator reports when the
+/// timer starts and stops, and hands over a buffer of PCM at
+/// `Chip8::sample_rate` for every 60 Hz timer tick. Outputs use whichever
+/// suits them.
+pub trait Audio {
+    /// Called with true when the sound timer starts running and false when it
+    /// runs out.
+    fn set_beeping(&mut self, _beeping: bool) -> Result<(), io::Error> {
+        Ok(())
+    }
+
+    /// The next stretch of 16 bit mono samples, silence while the sound timer
+    /// is stopped.
+    fn play_samples(&mut self, _samples: &[i16]) -> Result<(), io::Error> {
+        Ok(())
+    }
+
+    fn close_audio(&mut self);
+}
+
+/// Throws the sound away, used when no output has been set.
+pub struct SilentAudio;
+
+impl Audio for SilentAudio {
+    fn close_audio(&mut self) {}
+}
+
+/// Bits of the pattern played per second, 4000 * 2 ^ ((pitch - 64) / 48).
+pub fn playback_rate(pitch: u8) -> f64 {
+    4000.0 * 2f64.powf((pitch as f64 - 64.0) / 48.0)
+}
+
+/// Turns the audio pattern into 16 bit mono PCM.
+pub struct Synthesizer {
+    sample_rate: u32,
+    // Position in the pattern in bits, kept between calls so the waveform
+    // carries on smoothly from one buffer to the next
+    position: f64,
+}
+
+impl Synthesizer {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate: sample_rate.max(1),
+            position: 0.0,
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Fills `samples` with the pattern played at `pitch`, or with silence
+    /// when `playing` is false.
+    pub fn generate(
+        &mut self,
+        pattern: &[u8; PATTERN_SIZE],
+        pitch: u8,
+        playing: bool,
+        samples: &mut [i16],
+    ) {
+        if !playing {
+            samples.fill(0);
+            self.position = 0.0;
+            return;
+        }
+        let step = playback_rate(pitch) / self.sample_rate as f64;
+        for sample in samples {
+            let bit = self.position as usize;
+            let high = (pattern[bit / 8] >> (7 - bit % 8)) & 1 != 0;
+            *sample = if high { AMPLITUDE } else { -AMPLITUDE };
+            self.position = (self.position + step) % PATTERN_BITS;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::audio::{playback_rate, Synthesizer, DEFAULT_PATTERN, PATTERN_SIZE};
+
+    #[test]
+    fn test_playback_rate() {
+        assert_eq!(playback_rate(64), 4000.0);
+        assert_eq!(playback_rate(112), 8000.0);
+        assert_eq!(playback_rate(16), 2000.0);
+        assert!((playback_rate(0) - 4000.0 * 2f64.powf(-64.0 / 48.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_default_pitch_plays_each_bit_for_fixed_samples() {
+        let mut synthesizer = Synthesizer::new(8000);
+        let mut pattern = [0; PATTERN_SIZE];
+        pattern[0] = 0b1010_0000;
+        let mut samples = [0; 10];
+
+        synthesizer.generate(&pattern, 64, true, &mut samples);
+
+        // 4000 bits per second at 8000 samples per second is two samples a bit
+        let high: Vec<bool> = samples.iter().map(|sample| *sample > 0).collect();
+        assert_eq!(
+            high,
+            [true, true, false, false, true, true, false, false, false, false]
+        );
+    }
+
+    #[test]
+    fn test_waveform_matches_playback_rate() {
+        // Alternating bits, so every edge in the waveform is one bit played.
+        // The sample rate is high enough for even the top pitch not to alias.
+        let pattern = [0xAA; PATTERN_SIZE];
+        for pitch in [0, 32, 64, 100, 200, 255] {
+            let mut synthesizer = Synthesizer::new(192000);
+            let mut samples = vec![0; 192000];
+
+            synthesizer.generate(&pattern, pitch, true, &mut samples);
+
+            let edges = samples
+                .windows(2)
+                .filter(|pair| (pair[0] > 0) != (pair[1] > 0))
+                .count() as f64;
+            let expected = playback_rate(pitch);
+            assert!(
+                (edges - expected).abs() <= 1.0,
+                "pitch {} played {} bits a second, expected {}",
+                pitch,
+                edges,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_waveform_continues_between_buffers() {
+        let mut whole = Synthesizer::new(44100);
+        let mut split = Synthesizer::new(44100);
+        let mut expected = [0; 1000];
+        let mut samples = [0; 1000];
+
+        whole.generate(&DEFAULT_PATTERN, 90, true, &mut expected);
+        split.generate(&DEFAULT_PATTERN, 90, true, &mut samples[..333]);
+        split.generate(&DEFAULT_PATTERN, 90, true, &mut samples[333..]);
+
+        assert_eq!(samples, expected);
+    }
+
+    #[test]
+    fn test_silence() {
+        let mut synthesizer = Synthesizer::new(44100);
+        let mut samples = [1; 64];
+
+        synthesizer.generate(&DEFAULT_PATTERN, 64, false, &mut samples);
+
+        assert_eq!(samples, [0; 64]);
+    }
+}
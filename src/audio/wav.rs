@@ -0,0 +1,134 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use super::Audio;
+
+const HEADER_SIZE: u32 = 44;
+const BYTES_PER_SAMPLE: u16 = 2;
+
+/// Writes the sound to a 16 bit mono WAV file, so it can be checked without a
+/// sound card.
+pub struct WavAudio<W: Write + Seek> {
+    // Taken by `into_inner`, after which there's nothing left to finish
+    writer: Option<W>,
+    data_size: u32,
+}
+
+impl WavAudio<BufWriter<File>> {
+    pub fn create(path: impl AsRef<Path>, sample_rate: u32) -> Result<Self, io::Error> {
+        WavAudio::new(BufWriter::new(File::create(path)?), sample_rate)
+    }
+}
+
+impl<W: Write + Seek> WavAudio<W> {
+    /// Writes the header straight away. The sizes in it are filled in by
+    /// `close_audio`, or when the `WavAudio` is dropped.
+    pub fn new(mut writer: W, sample_rate: u32) -> Result<Self, io::Error> {
+        writer.write_all(b"RIFF")?;
+        writer.write_all(&(HEADER_SIZE - 8).to_le_bytes())?;
+        writer.write_all(b"WAVE")?;
+        writer.write_all(b"fmt ")?;
+        writer.write_all(&16u32.to_le_bytes())?;
+        // Uncompressed PCM, one channel
+        writer.write_all(&1u16.to_le_bytes())?;
+        writer.write_all(&1u16.to_le_bytes())?;
+        writer.write_all(&sample_rate.to_le_bytes())?;
+        writer.write_all(&(sample_rate * BYTES_PER_SAMPLE as u32).to_le_bytes())?;
+        writer.write_all(&BYTES_PER_SAMPLE.to_le_bytes())?;
+        writer.write_all(&(BYTES_PER_SAMPLE * 8).to_le_bytes())?;
+        writer.write_all(b"data")?;
+        writer.write_all(&0u32.to_le_bytes())?;
+        Ok(WavAudio {
+            writer: Some(writer),
+            data_size: 0,
+        })
+    }
+
+    /// Fills in the sizes in the header.
+    fn finish(&mut self) -> Result<(), io::Error> {
+        let Some(writer) = &mut self.writer else {
+            return Ok(());
+        };
+        writer.seek(SeekFrom::Start(4))?;
+        writer.write_all(&(HEADER_SIZE - 8 + self.data_size).to_le_bytes())?;
+        writer.seek(SeekFrom::Start(HEADER_SIZE as u64 - 4))?;
+        writer.write_all(&self.data_size.to_le_bytes())?;
+        writer.seek(SeekFrom::End(0))?;
+        writer.flush()
+    }
+
+    /// Finishes the file and hands back the writer.
+    pub fn into_inner(mut self) -> Result<W, io::Error> {
+        self.finish()?;
+        Ok(self.writer.take().expect("writer is only taken here"))
+    }
+}
+
+impl<W: Write + Seek> Audio for WavAudio<W> {
+    fn play_samples(&mut self, samples: &[i16]) -> Result<(), io::Error> {
+        let Some(writer) = &mut self.writer else {
+            return Ok(());
+        };
+        for sample in samples {
+            writer.write_all(&sample.to_le_bytes())?;
+        }
+        self.data_size += samples.len() as u32 * BYTES_PER_SAMPLE as u32;
+        Ok(())
+    }
+
+    fn close_audio(&mut self) {
+        let _ = self.finish();
+    }
+}
+
+impl<W: Write + Seek> Drop for WavAudio<W> {
+    fn drop(&mut self) {
+        let _ = self.finish();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use crate::audio::{wav::WavAudio, Audio};
+
+    #[test]
+    fn test_wav_file() {
+        let mut audio = WavAudio::new(Cursor::new(Vec::new()), 8000).unwrap();
+
+        audio.play_samples(&[1, -2]).unwrap();
+        audio.play_samples(&[0x1234]).unwrap();
+        let bytes = audio.into_inner().unwrap().into_inner();
+
+        assert_eq!(bytes.len(), 44 + 6);
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(bytes[4..8], 42u32.to_le_bytes());
+        assert_eq!(&bytes[8..16], b"WAVEfmt ");
+        // PCM, mono, 8000 Hz, 16000 bytes a second, 2 byte frames, 16 bits
+        assert_eq!(bytes[20..22], 1u16.to_le_bytes());
+        assert_eq!(bytes[22..24], 1u16.to_le_bytes());
+        assert_eq!(bytes[24..28], 8000u32.to_le_bytes());
+        assert_eq!(bytes[28..32], 16000u32.to_le_bytes());
+        assert_eq!(bytes[32..34], 2u16.to_le_bytes());
+        assert_eq!(bytes[34..36], 16u16.to_le_bytes());
+        assert_eq!(&bytes[36..40], b"data");
+        assert_eq!(bytes[40..44], 6u32.to_le_bytes());
+        assert_eq!(bytes[44..], [0x01, 0x00, 0xFE, 0xFF, 0x34, 0x12]);
+    }
+
+    #[test]
+    fn test_close_finishes_file() {
+        let mut audio = WavAudio::new(Cursor::new(Vec::new()), 44100).unwrap();
+
+        audio.play_samples(&[0; 10]).unwrap();
+        audio.close_audio();
+        let bytes = audio.into_inner().unwrap().into_inner();
+
+        assert_eq!(bytes.len(), 44 + 20);
+        assert_eq!(bytes[40..44], 20u32.to_le_bytes());
+    }
+}
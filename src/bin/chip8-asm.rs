@@ -0,0 +1,131 @@
+use std::{env, fs, path::PathBuf, process};
+
+use chip8::assembler::assemble;
+
+const USAGE: &str = "\
+Usage: chip8-asm [OPTIONS] <SOURCE>
+
+Assembles Octo source into a ROM.
+
+Options:
+  -o, --output <PATH>        Where to write the ROM [default: SOURCE with a .ch8 extension]
+  -h, --help                 Print this help";
+
+#[derive(Debug, PartialEq, Eq)]
+struct Args {
+    source_path: PathBuf,
+    output_path: PathBuf,
+}
+
+enum Action {
+    Assemble(Args),
+    Help,
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Action, String> {
+    let mut source_path = None;
+    let mut output_path = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Action::Help),
+            "-o" | "--output" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| format!("{} needs a value", arg))?;
+                output_path = Some(PathBuf::from(path));
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+            _ if source_path.is_some() => return Err(format!("unexpected argument '{}'", arg)),
+            _ => source_path = Some(PathBuf::from(arg)),
+        }
+    }
+
+    let source_path = source_path.ok_or("no source file given")?;
+    let output_path = output_path.unwrap_or_else(|| source_path.with_extension("ch8"));
+    Ok(Action::Assemble(Args {
+        source_path,
+        output_path,
+    }))
+}
+
+fn main() {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(Action::Assemble(args)) => args,
+        Ok(Action::Help) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let source = match fs::read_to_string(&args.source_path) {
+        Ok(source) => source,
+        Err(error) => {
+            eprintln!(
+                "error: failed to read '{}': {}",
+                args.source_path.display(),
+                error
+            );
+            process::exit(1);
+        }
+    };
+
+    let rom = match assemble(&source) {
+        Ok(rom) => rom,
+        Err(error) => {
+            eprintln!("error: {}: {}", args.source_path.display(), error);
+            process::exit(1);
+        }
+    };
+    if let Err(error) = fs::write(&args.output_path, rom) {
+        eprintln!(
+            "error: failed to write '{}': {}",
+            args.output_path.display(),
+            error
+        );
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Action, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_default_output_path() {
+        let Ok(Action::Assemble(args)) = parse(&["games/pong.8o"]) else {
+            panic!("expected assemble");
+        };
+
+        assert_eq!(
+            args,
+            Args {
+                source_path: PathBuf::from("games/pong.8o"),
+                output_path: PathBuf::from("games/pong.ch8"),
+            }
+        );
+    }
+
+    #[test]
+    fn test_output_option_and_errors() {
+        let Ok(Action::Assemble(args)) = parse(&["-o", "out.ch8", "pong.8o"]) else {
+            panic!("expected assemble");
+        };
+        assert_eq!(args.output_path, PathBuf::from("out.ch8"));
+
+        assert_eq!(parse(&[]).err(), Some("no source file given".to_string()));
+        assert_eq!(
+            parse(&["a.8o", "b.8o"]).err(),
+            Some("unexpected argument 'b.8o'".to_string())
+        );
+    }
+}
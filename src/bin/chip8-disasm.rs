@@ -0,0 +1,128 @@
+use std::{env, fs, process};
+
+use chip8::disassembler::disassemble;
+
+const USAGE: &str = "\
+Usage: chip8-disasm [OPTIONS] <ROM>
+
+Prints the ROM as Octo assembly.
+
+Options:
+  --xochip                   Read F000 NNNN as XO-CHIP's four byte instruction
+  -o, --output <PATH>        Write the assembly to a file instead of stdout
+  -h, --help                 Print this help";
+
+#[derive(Debug, PartialEq, Eq)]
+struct Args {
+    rom_path: String,
+    xo_chip: bool,
+    output_path: Option<String>,
+}
+
+enum Action {
+    Disassemble(Args),
+    Help,
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Action, String> {
+    let mut rom_path = None;
+    let mut xo_chip = false;
+    let mut output_path = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Action::Help),
+            "--xochip" => xo_chip = true,
+            "-o" | "--output" => {
+                output_path = Some(
+                    args.next()
+                        .ok_or_else(|| format!("{} needs a value", arg))?,
+                );
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+            _ if rom_path.is_some() => return Err(format!("unexpected argument '{}'", arg)),
+            _ => rom_path = Some(arg),
+        }
+    }
+
+    let rom_path = rom_path.ok_or("no ROM given")?;
+    Ok(Action::Disassemble(Args {
+        rom_path,
+        xo_chip,
+        output_path,
+    }))
+}
+
+fn main() {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(Action::Disassemble(args)) => args,
+        Ok(Action::Help) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let program = match fs::read(&args.rom_path) {
+        Ok(program) => program,
+        Err(error) => {
+            eprintln!("error: failed to read ROM '{}': {}", args.rom_path, error);
+            process::exit(1);
+        }
+    };
+
+    let assembly = disassemble(&program, args.xo_chip);
+    match &args.output_path {
+        Some(path) => {
+            if let Err(error) = fs::write(path, assembly) {
+                eprintln!("error: failed to write '{}': {}", path, error);
+                process::exit(1);
+            }
+        }
+        None => print!("{}", assembly),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Action, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_args() {
+        let Ok(Action::Disassemble(args)) = parse(&["--xochip", "game.ch8", "-o", "game.8o"])
+        else {
+            panic!("expected disassemble");
+        };
+
+        assert_eq!(
+            args,
+            Args {
+                rom_path: "game.ch8".to_string(),
+                xo_chip: true,
+                output_path: Some("game.8o".to_string()),
+            }
+        );
+        assert!(matches!(parse(&["-h"]), Ok(Action::Help)));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(parse(&[]).err(), Some("no ROM given".to_string()));
+        assert_eq!(
+            parse(&["game.ch8", "-o"]).err(),
+            Some("-o needs a value".to_string())
+        );
+        assert_eq!(
+            parse(&["--quiet", "game.ch8"]).err(),
+            Some("unknown option '--quiet'".to_string())
+        );
+    }
+}
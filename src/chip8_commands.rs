@@ -1,49 +1,136 @@
-#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
-pub(crate) enum Chip8Commands {
+use crate::commands::add::Add;
+use crate::commands::add_to_index::AddToIndex;
+use crate::commands::add_value_to_register::AddValueToRegister;
+use crate::commands::and::And;
+use crate::commands::binary_coded_decimal::BinaryCodedDecimal;
+use crate::commands::call::Call;
+use crate::commands::clear_screen::ClearScreen;
+use crate::commands::command::Command;
+use crate::commands::draw::Draw;
+use crate::commands::exit::Exit;
+use crate::commands::font_character::{BigFontCharacter, FontCharacter};
+use crate::commands::high_resolution::HighResolution;
+use crate::commands::jump::{Jump, JumpWithOffset};
+use crate::commands::load::Load;
+use crate::commands::load_audio_pattern::LoadAudioPattern;
+use crate::commands::low_resolution::LowResolution;
+use crate::commands::or::Or;
+use crate::commands::random::Random;
+use crate::commands::read_delay_timer::ReadDelayTimer;
+use crate::commands::read_flags::ReadFlags;
+use crate::commands::read_into_registers::ReadIntoRegisters;
+use crate::commands::read_register_range::ReadRegisterRange;
+use crate::commands::return_command::Return;
+use crate::commands::scroll_down::ScrollDown;
+use crate::commands::scroll_left::ScrollLeft;
+use crate::commands::scroll_right::ScrollRight;
+use crate::commands::select_planes::SelectPlanes;
+use crate::commands::set_delay_timer::SetDelayTimer;
+use crate::commands::set_index_register::SetIndexRegister;
+use crate::commands::set_pitch::SetPitch;
+use crate::commands::set_register::SetRegister;
+use crate::commands::set_sound_timer::SetSoundTimer;
+use crate::commands::shift_left::ShiftLeft;
+use crate::commands::shift_right::ShiftRight;
+use crate::commands::skip_equal_x::SkipEqualX;
+use crate::commands::skip_equal_x_y::SkipEqualXY;
+use crate::commands::skip_key_not_pressed::SkipKeyNotPressed;
+use crate::commands::skip_key_pressed::SkipKeyPressed;
+use crate::commands::skip_not_equal_x::SkipNotEqualX;
+use crate::commands::skip_not_equal_xy::SkipNotEqualXY;
+use crate::commands::store_flags::StoreFlags;
+use crate::commands::store_register_range::StoreRegisterRange;
+use crate::commands::store_registers::StoreRegisters;
+use crate::commands::sub::Sub;
+use crate::commands::sub_n::SubN;
+use crate::commands::wait_for_key::WaitForKey;
+use crate::commands::xor::Xor;
+use crate::decode_error::{DecodeError, DecodeErrorKind};
+use crate::Chip8;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Chip8Commands {
+    ScrollDown(u8),             // 00CN
     ClearScreen,                // 00E0
     Return,                     // 00EE
+    ScrollRight,                // 00FB
+    ScrollLeft,                 // 00FC
+    Exit,                       // 00FD
+    LowResolution,              // 00FE
+    HighResolution,             // 00FF
     Jump(u16),                  // 1NNN
     Call(u16),                  // 2NNN
     SkipEqualX(u8, u8),         // 3XNN
     SkipNotEqualX(u8, u8),      // 4XNN
     SkipEqualXY(u8, u8),        // 5XY0
+    StoreRegisterRange(u8, u8), // 5XY2
+    ReadRegisterRange(u8, u8),  // 5XY3
     SetRegister(u8, u8),        // 6XNN
     AddValueToRegister(u8, u8), // 7XNN
     Load(u8, u8),               // 8XY0
-    OR(u8, u8),                 // 8XY1
-    AND(u8, u8),                // 8XY2
-    XOR(u8, u8),                // 8XY3
-    ADD(u8, u8),                // 8XY4
-    SUB(u8, u8),                // 8XY5
+    Or(u8, u8),                 // 8XY1
+    And(u8, u8),                // 8XY2
+    Xor(u8, u8),                // 8XY3
+    Add(u8, u8),                // 8XY4
+    Sub(u8, u8),                // 8XY5
     ShiftRight(u8, u8),         // 8XY6
-    SUBN(u8, u8),               // 8XY7
+    SubN(u8, u8),               // 8XY7
     ShiftLeft(u8, u8),          // 8XYE
     SkipNotEqualXY(u8, u8),     // 9XY0
-    SetIndexRegister(u16),      // ANNN
+    SetIndexRegister(u16),      // ANNN, F000 NNNN
+    JumpWithOffset(u16),        // BNNN
+    Random(u8, u8),             // CXNN
     Draw(u8, u8, u8),           // DXYN
+    SkipKeyPressed(u8),         // EX9E
+    SkipKeyNotPressed(u8),      // EXA1
+    SelectPlanes(u8),           // FN01
+    LoadAudioPattern,           // F002
+    ReadDelayTimer(u8),         // FX07
+    WaitForKey(u8),             // FX0A
+    SetDelayTimer(u8),          // FX15
+    SetSoundTimer(u8),          // FX18
     AddToIndex(u8),             // Fx1E
+    FontCharacter(u8),          // FX29
+    BigFontCharacter(u8),       // FX30
+    SetPitch(u8),               // FX3A
     BinaryCodedDecimal(u8),     // FX33
     StoreRegisters(u8),         // FX55
     ReadIntoRegisters(u8),      // FX65
+    StoreFlags(u8),             // FX75
+    ReadFlags(u8),              // FX85
 }
 
 impl Chip8Commands {
-    pub fn new(command: &[u8]) -> Chip8Commands {
+    /// Decodes the instruction at `address`. `command` is two bytes long, or
+    /// four for XO-CHIP's F000 NNNN.
+    pub fn new(address: u16, command: &[u8]) -> Result<Chip8Commands, DecodeError> {
         let opcode = (command[0] & 0xF0) >> 4;
-        match opcode {
+        let decoded = match opcode {
             0 => match command {
+                [0x00, n] if n & 0xF0 == 0xC0 => Chip8Commands::ScrollDown(n & 0xF),
                 [0x00, 0xE0] => Chip8Commands::ClearScreen,
                 [0x00, 0xEE] => Chip8Commands::Return,
-                _ => panic!("0NNN command can't be run since it is dependant on specific hardware"),
+                [0x00, 0xFB] => Chip8Commands::ScrollRight,
+                [0x00, 0xFC] => Chip8Commands::ScrollLeft,
+                [0x00, 0xFD] => Chip8Commands::Exit,
+                [0x00, 0xFE] => Chip8Commands::LowResolution,
+                [0x00, 0xFF] => Chip8Commands::HighResolution,
+                _ => {
+                    return Err(DecodeError::new(
+                        address,
+                        command,
+                        DecodeErrorKind::MachineCodeRoutine,
+                    ))
+                }
             },
             3 | 4 | 6 | 7 => {
                 let x = command[0] & 0xF;
                 match opcode {
-                    3 => Chip8Commands::SkipEqualX(x.into(), command[1]),
-                    4 => Chip8Commands::SkipNotEqualX(x.into(), command[1]),
-                    6 => Chip8Commands::SetRegister(x.into(), command[1]),
-                    7 => Chip8Commands::AddValueToRegister(x.into(), command[1]),
-                    _ => panic!("Instruction {:x?} not found", command),
+                    3 => Chip8Commands::SkipEqualX(x, command[1]),
+                    4 => Chip8Commands::SkipNotEqualX(x, command[1]),
+                    6 => Chip8Commands::SetRegister(x, command[1]),
+                    7 => Chip8Commands::AddValueToRegister(x, command[1]),
+                    _ => return Err(DecodeError::unknown(address, command)),
                 }
             }
             8 => {
@@ -51,54 +138,155 @@ impl Chip8Commands {
                 let y = (command[1] >> 4) & 0xF;
                 let identifier = command[1] & 0xF;
                 match identifier {
-                    0x0 => Chip8Commands::Load(x.into(), y.into()),
-                    0x1 => Chip8Commands::OR(x.into(), y.into()),
-                    0x2 => Chip8Commands::AND(x.into(), y.into()),
-                    0x3 => Chip8Commands::XOR(x.into(), y.into()),
-                    0x4 => Chip8Commands::ADD(x.into(), y.into()),
-                    0x5 => Chip8Commands::SUB(x.into(), y.into()),
-                    0x6 => Chip8Commands::ShiftRight(x.into(), y.into()),
-                    0x7 => Chip8Commands::SUBN(x.into(), y.into()),
-                    0xE => Chip8Commands::ShiftLeft(x.into(), y.into()),
-                    _ => panic!("Instruction {:x?} not found", command),
+                    0x0 => Chip8Commands::Load(x, y),
+                    0x1 => Chip8Commands::Or(x, y),
+                    0x2 => Chip8Commands::And(x, y),
+                    0x3 => Chip8Commands::Xor(x, y),
+                    0x4 => Chip8Commands::Add(x, y),
+                    0x5 => Chip8Commands::Sub(x, y),
+                    0x6 => Chip8Commands::ShiftRight(x, y),
+                    0x7 => Chip8Commands::SubN(x, y),
+                    0xE => Chip8Commands::ShiftLeft(x, y),
+                    _ => return Err(DecodeError::unknown(address, command)),
+                }
+            }
+            0xE => {
+                let x = command[0] & 0xF;
+                match command[1] {
+                    0x9E => Chip8Commands::SkipKeyPressed(x),
+                    0xA1 => Chip8Commands::SkipKeyNotPressed(x),
+                    _ => return Err(DecodeError::unknown(address, command)),
                 }
             }
             0xF => {
                 let x = command[0] & 0xF;
                 match command[1] {
-                    0x1E => Chip8Commands::AddToIndex(x.into()),
-                    0x33 => Chip8Commands::BinaryCodedDecimal(x.into()),
-                    0x55 => Chip8Commands::StoreRegisters(x.into()),
-                    0x65 => Chip8Commands::ReadIntoRegisters(x.into()),
-                    _ => panic!("Instruction {:x?} not found", command),
+                    0x00 if x == 0 && command.len() == 4 => {
+                        let index = u16::from_be_bytes([command[2], command[3]]);
+                        Chip8Commands::SetIndexRegister(index)
+                    }
+                    0x01 => Chip8Commands::SelectPlanes(x),
+                    0x02 if x == 0 => Chip8Commands::LoadAudioPattern,
+                    0x07 => Chip8Commands::ReadDelayTimer(x),
+                    0x0A => Chip8Commands::WaitForKey(x),
+                    0x15 => Chip8Commands::SetDelayTimer(x),
+                    0x18 => Chip8Commands::SetSoundTimer(x),
+                    0x1E => Chip8Commands::AddToIndex(x),
+                    0x29 => Chip8Commands::FontCharacter(x),
+                    0x30 => Chip8Commands::BigFontCharacter(x),
+                    0x3A => Chip8Commands::SetPitch(x),
+                    0x33 => Chip8Commands::BinaryCodedDecimal(x),
+                    0x55 => Chip8Commands::StoreRegisters(x),
+                    0x65 => Chip8Commands::ReadIntoRegisters(x),
+                    0x75 => Chip8Commands::StoreFlags(x),
+                    0x85 => Chip8Commands::ReadFlags(x),
+                    _ => return Err(DecodeError::unknown(address, command)),
                 }
             }
-            1 | 2 | 0xA => {
+            1 | 2 | 0xA | 0xB => {
                 let address = ((command[0] as u16 & 0xF) << 8) | command[1] as u16;
                 match opcode {
                     1 => Chip8Commands::Jump(address),
                     2 => Chip8Commands::Call(address),
                     0xA => Chip8Commands::SetIndexRegister(address),
-                    _ => panic!("Instruction {:x?} not found", command),
+                    0xB => Chip8Commands::JumpWithOffset(address),
+                    _ => return Err(DecodeError::unknown(address, command)),
                 }
             }
             5 => {
                 let x = command[0] & 0xF;
                 let y = (command[1] >> 4) & 0xF;
-                Chip8Commands::SkipEqualXY(x.into(), y.into())
+                match command[1] & 0xF {
+                    0x0 => Chip8Commands::SkipEqualXY(x, y),
+                    0x2 => Chip8Commands::StoreRegisterRange(x, y),
+                    0x3 => Chip8Commands::ReadRegisterRange(x, y),
+                    _ => return Err(DecodeError::unknown(address, command)),
+                }
             }
             9 => {
                 let x = command[0] & 0xF;
                 let y = (command[1] >> 4) & 0xF;
-                Chip8Commands::SkipNotEqualXY(x.into(), y.into())
+                Chip8Commands::SkipNotEqualXY(x, y)
+            }
+            0xC => {
+                let x = command[0] & 0xF;
+                Chip8Commands::Random(x, command[1])
             }
             0xD => {
                 let x = command[0] & 0xF;
                 let y = (command[1] & 0xF0) >> 4;
                 let bytes = command[1] & 0xF;
-                Chip8Commands::Draw(x.into(), y.into(), bytes)
+                Chip8Commands::Draw(x, y, bytes)
+            }
+            _ => return Err(DecodeError::unknown(address, command)),
+        };
+        Ok(decoded)
+    }
+}
+
+impl Command for Chip8Commands {
+    fn execute(&self, emulator: &mut Chip8) {
+        match *self {
+            Chip8Commands::ScrollDown(rows) => ScrollDown::new(rows).execute(emulator),
+            Chip8Commands::ClearScreen => ClearScreen::new().execute(emulator),
+            Chip8Commands::Return => Return::new().execute(emulator),
+            Chip8Commands::ScrollRight => ScrollRight::new().execute(emulator),
+            Chip8Commands::ScrollLeft => ScrollLeft::new().execute(emulator),
+            Chip8Commands::Exit => Exit::new().execute(emulator),
+            Chip8Commands::LowResolution => LowResolution::new().execute(emulator),
+            Chip8Commands::HighResolution => HighResolution::new().execute(emulator),
+            Chip8Commands::Jump(address) => Jump::new(address).execute(emulator),
+            Chip8Commands::Call(address) => Call::new(address).execute(emulator),
+            Chip8Commands::SkipEqualX(x, value) => SkipEqualX::new(x, value).execute(emulator),
+            Chip8Commands::SkipNotEqualX(x, value) => {
+                SkipNotEqualX::new(x, value).execute(emulator)
+            }
+            Chip8Commands::SkipEqualXY(x, y) => SkipEqualXY::new(x, y).execute(emulator),
+            Chip8Commands::StoreRegisterRange(x, y) => {
+                StoreRegisterRange::new(x, y).execute(emulator)
+            }
+            Chip8Commands::ReadRegisterRange(x, y) => {
+                ReadRegisterRange::new(x, y).execute(emulator)
             }
-            _ => panic!("Instruction {:x?} not found", command),
+            Chip8Commands::SetRegister(x, value) => SetRegister::new(x, value).execute(emulator),
+            Chip8Commands::AddValueToRegister(x, value) => {
+                AddValueToRegister::new(x, value).execute(emulator)
+            }
+            Chip8Commands::Load(x, y) => Load::new(x, y).execute(emulator),
+            Chip8Commands::Or(x, y) => Or::new(x, y).execute(emulator),
+            Chip8Commands::And(x, y) => And::new(x, y).execute(emulator),
+            Chip8Commands::Xor(x, y) => Xor::new(x, y).execute(emulator),
+            Chip8Commands::Add(x, y) => Add::new(x, y).execute(emulator),
+            Chip8Commands::Sub(x, y) => Sub::new(x, y).execute(emulator),
+            Chip8Commands::ShiftRight(x, y) => ShiftRight::new(x, y).execute(emulator),
+            Chip8Commands::SubN(x, y) => SubN::new(x, y).execute(emulator),
+            Chip8Commands::ShiftLeft(x, y) => ShiftLeft::new(x, y).execute(emulator),
+            Chip8Commands::SkipNotEqualXY(x, y) => SkipNotEqualXY::new(x, y).execute(emulator),
+            Chip8Commands::SetIndexRegister(address) => {
+                SetIndexRegister::new(address).execute(emulator)
+            }
+            Chip8Commands::JumpWithOffset(address) => {
+                JumpWithOffset::new(address).execute(emulator)
+            }
+            Chip8Commands::Random(x, value) => Random::new(x, value).execute(emulator),
+            Chip8Commands::Draw(x, y, bytes) => Draw::new(x, y, bytes).execute(emulator),
+            Chip8Commands::SkipKeyPressed(x) => SkipKeyPressed::new(x).execute(emulator),
+            Chip8Commands::SkipKeyNotPressed(x) => SkipKeyNotPressed::new(x).execute(emulator),
+            Chip8Commands::SelectPlanes(planes) => SelectPlanes::new(planes).execute(emulator),
+            Chip8Commands::LoadAudioPattern => LoadAudioPattern::new().execute(emulator),
+            Chip8Commands::ReadDelayTimer(x) => ReadDelayTimer::new(x).execute(emulator),
+            Chip8Commands::WaitForKey(x) => WaitForKey::new(x).execute(emulator),
+            Chip8Commands::SetDelayTimer(x) => SetDelayTimer::new(x).execute(emulator),
+            Chip8Commands::SetSoundTimer(x) => SetSoundTimer::new(x).execute(emulator),
+            Chip8Commands::AddToIndex(x) => AddToIndex::new(x).execute(emulator),
+            Chip8Commands::FontCharacter(x) => FontCharacter::new(x).execute(emulator),
+            Chip8Commands::BigFontCharacter(x) => BigFontCharacter::new(x).execute(emulator),
+            Chip8Commands::SetPitch(x) => SetPitch::new(x).execute(emulator),
+            Chip8Commands::BinaryCodedDecimal(x) => BinaryCodedDecimal::new(x).execute(emulator),
+            Chip8Commands::StoreRegisters(x) => StoreRegisters::new(x).execute(emulator),
+            Chip8Commands::ReadIntoRegisters(x) => ReadIntoRegisters::new(x).execute(emulator),
+            Chip8Commands::StoreFlags(x) => StoreFlags::new(x).execute(emulator),
+            Chip8Commands::ReadFlags(x) => ReadFlags::new(x).execute(emulator),
         }
     }
 }
@@ -135,6 +323,30 @@ mod test {
             [0x83, 0x67],
             [0xF1, 0x65],
             [0xF1, 0x1E],
+            [0xF4, 0x07],
+            [0xF5, 0x15],
+            [0xF6, 0x18],
+            [0xE7, 0x9E],
+            [0xE8, 0xA1],
+            [0xF9, 0x0A],
+            [0xC2, 0x3F],
+            [0xB3, 0x45],
+            [0xFA, 0x29],
+            [0x00, 0xFE],
+            [0x00, 0xFF],
+            [0x00, 0xC7],
+            [0x00, 0xFB],
+            [0x00, 0xFC],
+            [0xF4, 0x30],
+            [0xD1, 0x20],
+            [0xF7, 0x75],
+            [0xF3, 0x85],
+            [0x00, 0xFD],
+            [0x51, 0x42],
+            [0x5E, 0x23],
+            [0xF3, 0x01],
+            [0xF0, 0x02],
+            [0xF6, 0x3A],
         ];
         let expected = [
             Chip8Commands::ClearScreen,
@@ -148,27 +360,112 @@ mod test {
             Chip8Commands::SkipNotEqualX(1, 0x45),
             Chip8Commands::SkipEqualXY(0xA, 0xD),
             Chip8Commands::Load(7, 0xA),
-            Chip8Commands::OR(9, 2),
-            Chip8Commands::AND(0xA, 3),
-            Chip8Commands::XOR(0xB, 4),
-            Chip8Commands::ADD(0xC, 5),
-            Chip8Commands::SUB(0xD, 6),
+            Chip8Commands::Or(9, 2),
+            Chip8Commands::And(0xA, 3),
+            Chip8Commands::Xor(0xB, 4),
+            Chip8Commands::Add(0xC, 5),
+            Chip8Commands::Sub(0xD, 6),
             Chip8Commands::ShiftRight(0xE, 7),
             Chip8Commands::ShiftLeft(0xF, 8),
             Chip8Commands::SkipNotEqualXY(0x0, 9),
             Chip8Commands::BinaryCodedDecimal(3),
             Chip8Commands::StoreRegisters(6),
             Chip8Commands::Call(0xA53),
-            Chip8Commands::SUBN(3, 6),
+            Chip8Commands::SubN(3, 6),
             Chip8Commands::ReadIntoRegisters(1),
             Chip8Commands::AddToIndex(1),
+            Chip8Commands::ReadDelayTimer(4),
+            Chip8Commands::SetDelayTimer(5),
+            Chip8Commands::SetSoundTimer(6),
+            Chip8Commands::SkipKeyPressed(7),
+            Chip8Commands::SkipKeyNotPressed(8),
+            Chip8Commands::WaitForKey(9),
+            Chip8Commands::Random(2, 0x3F),
+            Chip8Commands::JumpWithOffset(0x345),
+            Chip8Commands::FontCharacter(0xA),
+            Chip8Commands::LowResolution,
+            Chip8Commands::HighResolution,
+            Chip8Commands::ScrollDown(7),
+            Chip8Commands::ScrollRight,
+            Chip8Commands::ScrollLeft,
+            Chip8Commands::BigFontCharacter(4),
+            Chip8Commands::Draw(1, 2, 0),
+            Chip8Commands::StoreFlags(7),
+            Chip8Commands::ReadFlags(3),
+            Chip8Commands::Exit,
+            Chip8Commands::StoreRegisterRange(1, 4),
+            Chip8Commands::ReadRegisterRange(0xE, 2),
+            Chip8Commands::SelectPlanes(3),
+            Chip8Commands::LoadAudioPattern,
+            Chip8Commands::SetPitch(6),
         ];
 
         for (i, command) in commands.into_iter().enumerate() {
-            let result = Chip8Commands::new(&command);
+            let result = Chip8Commands::new(0x200, &command);
             let expected = &expected[i];
 
-            assert_eq!(result, *expected)
+            assert_eq!(result, Ok(*expected))
         }
     }
+
+    #[test]
+    fn test_long_command_decode() {
+        assert_eq!(
+            Chip8Commands::new(0x200, &[0xF0, 0x00, 0x12, 0x34]),
+            Ok(Chip8Commands::SetIndexRegister(0x1234))
+        );
+    }
+
+    #[test]
+    fn test_command_decode_errors() {
+        assert_eq!(
+            Chip8Commands::new(0x204, &[0x01, 0x23]),
+            Err(DecodeError::new(
+                0x204,
+                &[0x01, 0x23],
+                DecodeErrorKind::MachineCodeRoutine
+            ))
+        );
+        assert_eq!(
+            Chip8Commands::new(0x206, &[0x8A, 0xB8]),
+            Err(DecodeError::unknown(0x206, &[0x8A, 0xB8]))
+        );
+        assert_eq!(
+            Chip8Commands::new(0x208, &[0xE1, 0x00]),
+            Err(DecodeError::unknown(0x208, &[0xE1, 0x00]))
+        );
+        assert_eq!(
+            Chip8Commands::new(0x20A, &[0xF1, 0xFF]),
+            Err(DecodeError::unknown(0x20A, &[0xF1, 0xFF]))
+        );
+        assert_eq!(
+            Chip8Commands::new(0x20C, &[0x51, 0x21]),
+            Err(DecodeError::unknown(0x20C, &[0x51, 0x21]))
+        );
+        assert_eq!(
+            Chip8Commands::new(0x210, &[0xF1, 0x02]),
+            Err(DecodeError::unknown(0x210, &[0xF1, 0x02]))
+        );
+        assert_eq!(
+            Chip8Commands::new(0x20E, &[0xF0, 0x00]),
+            Err(DecodeError::unknown(0x20E, &[0xF0, 0x00]))
+        );
+    }
+
+    #[test]
+    fn test_execute_decoded_command() {
+        let mut emulator = Chip8::new();
+        emulator.registers[1] = 0xF0;
+
+        Chip8Commands::new(0x200, &[0x6A, 0x2B])
+            .unwrap()
+            .execute(&mut emulator);
+        Chip8Commands::new(0x202, &[0x81, 0xA4])
+            .unwrap()
+            .execute(&mut emulator);
+
+        assert_eq!(emulator.registers[0xA], 0x2B);
+        assert_eq!(emulator.registers[1], 0x1B);
+        assert_eq!(emulator.registers[0xF], 1);
+    }
 }
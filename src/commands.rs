@@ -8,20 +8,41 @@ pub mod clear_screen;
 pub mod command;
 pub mod command_parser;
 pub mod draw;
+pub mod exit;
+pub mod font_character;
+pub mod high_resolution;
 pub mod jump;
 pub mod load;
+pub mod load_audio_pattern;
+pub mod low_resolution;
 pub mod or;
+pub mod random;
+pub mod read_delay_timer;
+pub mod read_flags;
 pub mod read_into_registers;
+pub mod read_register_range;
 pub mod return_command;
+pub mod scroll_down;
+pub mod scroll_left;
+pub mod scroll_right;
+pub mod select_planes;
+pub mod set_delay_timer;
 pub mod set_index_register;
+pub mod set_pitch;
 pub mod set_register;
+pub mod set_sound_timer;
 pub mod shift_left;
 pub mod shift_right;
 pub mod skip_equal_x;
 pub mod skip_equal_x_y;
+pub mod skip_key_not_pressed;
+pub mod skip_key_pressed;
 pub mod skip_not_equal_x;
 pub mod skip_not_equal_xy;
+pub mod store_flags;
+pub mod store_register_range;
 pub mod store_registers;
 pub mod sub;
 pub mod sub_n;
+pub mod wait_for_key;
 pub mod xor;
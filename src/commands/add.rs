@@ -24,6 +24,7 @@ impl Command for Add {
     }
 }
 
+#[cfg(test)]
 mod test {
     use crate::Chip8;
     use crate::commands::add::Add;
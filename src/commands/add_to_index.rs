@@ -18,6 +18,7 @@ impl Command for AddToIndex {
     }
 }
 
+#[cfg(test)]
 mod test {
     use crate::Chip8;
     use crate::commands::add_to_index::AddToIndex;
@@ -19,6 +19,7 @@ impl Command for AddValueToRegister {
     }
 }
 
+#[cfg(test)]
 mod test {
     use crate::Chip8;
     use crate::commands::add_value_to_register::AddValueToRegister;
@@ -19,9 +19,13 @@ impl Command for And {
     fn execute(&self, emulator: &mut Chip8) {
         emulator.registers[self.register_x as usize] &=
             emulator.registers[self.register_y as usize];
+        if emulator.quirks.vf_reset {
+            emulator.registers[0xF] = 0;
+        }
     }
 }
 
+#[cfg(test)]
 mod test {
     use crate::Chip8;
     use crate::commands::and::And;
@@ -37,4 +41,18 @@ mod test {
 
         assert_eq!(emulator.registers[0], 1)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_bitwise_and_vf_reset() {
+        let mut emulator = Chip8::new();
+        emulator.quirks.vf_reset = true;
+        emulator.registers[0] = 0b10101111;
+        emulator.registers[5] = 0b01010001;
+        emulator.registers[0xF] = 0x55;
+
+        And::new(0, 5).execute(&mut emulator);
+
+        assert_eq!(emulator.registers[0], 1);
+        assert_eq!(emulator.registers[0xF], 0);
+    }
+}
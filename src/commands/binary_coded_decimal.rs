@@ -13,19 +13,19 @@ impl BinaryCodedDecimal {
 
 impl Command for BinaryCodedDecimal {
     fn execute(&self, emulator: &mut Chip8) {
-        emulator.memory[emulator.index_register as usize] =
-            emulator.registers[self.register as usize] / 100;
-        emulator.memory[emulator.index_register as usize + 1] =
-            emulator.registers[self.register as usize] % 100 / 10;
-        emulator.memory[emulator.index_register as usize + 2] =
-            emulator.registers[self.register as usize] % 100 % 10;
+        let value = emulator.registers[self.register as usize];
+        let address = emulator.index_register;
+        emulator.write_memory_byte(address, value / 100);
+        emulator.write_memory_byte(address.wrapping_add(1), value % 100 / 10);
+        emulator.write_memory_byte(address.wrapping_add(2), value % 100 % 10);
     }
 }
 
+#[cfg(test)]
 mod test {
-    use crate::Chip8;
     use crate::commands::binary_coded_decimal::BinaryCodedDecimal;
     use crate::commands::command::Command;
+    use crate::{Chip8, Chip8Options};
 
     #[test]
     fn test_binary_coded_decimal_htu() {
@@ -91,4 +91,33 @@ mod test {
         assert_eq!(emulator.memory[0x201], 3);
         assert_eq!(emulator.memory[0x202], 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_binary_coded_decimal_wraps_at_end_of_memory() {
+        let mut emulator = Chip8::new();
+        emulator.index_register = 0xFFF;
+        emulator.registers[0] = 235;
+
+        BinaryCodedDecimal::new(0).execute(&mut emulator);
+
+        assert_eq!(emulator.memory[0xFFF], 2);
+        assert_eq!(emulator.memory[0x000], 3);
+        assert_eq!(emulator.memory[0x001], 5);
+    }
+
+    #[test]
+    fn test_binary_coded_decimal_wraps_xo_chip() {
+        let mut emulator = Chip8::with_options(Chip8Options {
+            xo_chip: true,
+            ..Default::default()
+        });
+        emulator.index_register = 0xFFFF;
+        emulator.registers[0] = 235;
+
+        BinaryCodedDecimal::new(0).execute(&mut emulator);
+
+        assert_eq!(emulator.memory[0xFFFF], 2);
+        assert_eq!(emulator.memory[0x0000], 3);
+        assert_eq!(emulator.memory[0x0001], 5);
+    }
+}
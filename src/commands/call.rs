@@ -1,5 +1,6 @@
 use crate::commands::command::Command;
-use crate::Chip8;
+use crate::run_state::{HaltReason, RunState};
+use crate::{Chip8, STACK_SIZE};
 
 pub struct Call {
     address: u16,
@@ -13,15 +14,22 @@ impl Call {
 
 impl Command for Call {
     fn execute(&self, emulator: &mut Chip8) {
+        if emulator.stack.len() >= STACK_SIZE {
+            let address = emulator.program_counter.wrapping_sub(2);
+            emulator.run_state = RunState::Halted(HaltReason::StackOverflow { address });
+            return;
+        }
         emulator.stack.push(emulator.program_counter);
         emulator.program_counter = self.address;
     }
 }
 
+#[cfg(test)]
 mod test {
-    use crate::Chip8;
     use crate::commands::call::Call;
     use crate::commands::command::Command;
+    use crate::run_state::{HaltReason, RunState};
+    use crate::{Chip8, Chip8Options};
 
     #[test]
     fn test_call_function() {
@@ -34,4 +42,37 @@ mod test {
         assert_eq!(emulator.stack[0], 0x200);
         assert_eq!(emulator.program_counter, 0x543);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_call_stack_overflow() {
+        let mut emulator = Chip8::new();
+        emulator.stack = vec![0x200; 16];
+        emulator.program_counter = 0x30C;
+
+        Call::new(0x543).execute(&mut emulator);
+
+        assert_eq!(emulator.stack.len(), 16);
+        assert_eq!(
+            emulator.run_state(),
+            RunState::Halted(HaltReason::StackOverflow { address: 0x30A })
+        );
+    }
+
+    #[test]
+    fn test_call_stack_overflow_at_end_of_xo_chip_memory() {
+        let mut emulator = Chip8::with_options(Chip8Options {
+            xo_chip: true,
+            ..Default::default()
+        });
+        emulator.stack = vec![0x200; 16];
+        emulator.write_memory(0xFFFE, &[0x25, 0x43]);
+        emulator.program_counter = 0xFFFE;
+
+        emulator.step().unwrap();
+
+        assert_eq!(
+            emulator.run_state(),
+            RunState::Halted(HaltReason::StackOverflow { address: 0xFFFE })
+        );
+    }
+}
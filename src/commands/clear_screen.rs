@@ -11,15 +11,12 @@ impl ClearScreen {
 
 impl Command for ClearScreen {
     fn execute(&self, emulator: &mut Chip8) {
-        for row in emulator.display_data.iter_mut() {
-            for pixel in row {
-                *pixel = false;
-            }
-        }
+        emulator.framebuffer.clear_planes(emulator.selected_planes);
+        emulator.display_changed = true;
     }
 }
 
-
+#[cfg(test)]
 mod test {
     use crate::Chip8;
     use crate::commands::clear_screen::ClearScreen;
@@ -30,16 +27,28 @@ mod test {
         let mut emulator = Chip8::new();
         for x in 0..64 {
             for y in 0..32 {
-                emulator.display_data[x][y] = true;
+                emulator.framebuffer.set(x, y, true);
             }
         }
-    
+
         ClearScreen::new().execute(&mut emulator);
-    
-        for row in emulator.display_data {
-            for pixel in row {
-                assert!(!pixel)
+
+        for x in 0..64 {
+            for y in 0..32 {
+                assert!(!emulator.framebuffer.get(x, y))
             }
         }
+        assert!(emulator.display_changed);
+    }
+
+    #[test]
+    fn test_clear_selected_planes() {
+        let mut emulator = Chip8::new();
+        emulator.framebuffer.set_colour(0, 0, 0b11);
+        emulator.selected_planes = 0b10;
+
+        ClearScreen::new().execute(&mut emulator);
+
+        assert_eq!(emulator.framebuffer.colour(0, 0), 0b01);
     }
 }
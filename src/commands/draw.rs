@@ -1,4 +1,5 @@
 use crate::commands::command::Command;
+use crate::framebuffer::PLANE_COUNT;
 use crate::Chip8;
 
 pub struct Draw {
@@ -19,31 +20,68 @@ impl Draw {
 
 impl Command for Draw {
     fn execute(&self, emulator: &mut Chip8) {
-        let x_start = (emulator.registers[self.register_x as usize] as usize) % 64;
-        let y_start = (emulator.registers[self.register_y as usize] as usize) % 32;
-        for byte_offset in 0..self.bytes {
-            let byte = emulator.memory[emulator.index_register as usize + byte_offset as usize];
-            for i in 0..8 {
-                let bit = ((byte >> 7 - i) & 0b1) != 0;
-                let x_pos = x_start + i;
-                let y_pos = y_start + byte_offset as usize;
-                if x_pos < 64 && y_pos < 32 {
-                    if emulator.display_data[x_pos][y_pos] != bit {
-                        emulator.registers[0xF] = 1;
+        let width = emulator.framebuffer.width();
+        let height = emulator.framebuffer.height();
+        let x_start = (emulator.registers[self.register_x as usize] as usize) % width;
+        let y_start = (emulator.registers[self.register_y as usize] as usize) % height;
+        // SUPER-CHIP draws a 16x16 sprite, two bytes per row, for DXY0
+        let (sprite_width, rows) = if self.bytes == 0 {
+            (16, 16)
+        } else {
+            (8, self.bytes as usize)
+        };
+        let bytes_per_row = sprite_width / 8;
+        // Each selected plane gets its own copy of the sprite, one after the
+        // other in memory starting at I
+        let mut sprite_address = emulator.index_register;
+        let mut collided = [false; 16];
+        for plane in (0..PLANE_COUNT).map(|plane| 1 << plane) {
+            if emulator.selected_planes & plane == 0 {
+                continue;
+            }
+            for (row, collided) in collided.iter_mut().enumerate().take(rows) {
+                for i in 0..sprite_width {
+                    let offset = (row * bytes_per_row + i / 8) as u16;
+                    let byte = emulator.read_memory(sprite_address.wrapping_add(offset));
+                    let bit = ((byte >> (7 - i % 8)) & 0b1) != 0;
+                    let mut x_pos = x_start + i;
+                    let mut y_pos = y_start + row;
+                    if !emulator.quirks.clipping {
+                        x_pos %= width;
+                        y_pos %= height;
+                    }
+                    if bit
+                        && x_pos < width
+                        && y_pos < height
+                        && emulator.framebuffer.flip(x_pos, y_pos, plane)
+                    {
+                        *collided = true;
                     }
-                    emulator.display_data[x_pos][y_pos] ^= bit;
                 }
             }
+            sprite_address = sprite_address.wrapping_add((rows * bytes_per_row) as u16);
         }
+        let collided_rows = collided.iter().filter(|collided| **collided).count() as u8;
+        emulator.registers[0xF] =
+            if emulator.quirks.collision_counts_rows && emulator.framebuffer.is_high_res() {
+                collided_rows
+            } else {
+                (collided_rows > 0) as u8
+            };
 
         emulator.display_changed = true;
+        if emulator.quirks.display_wait {
+            emulator.waiting_for_vblank = true;
+        }
     }
 }
 
+#[cfg(test)]
 mod test {
     use crate::Chip8;
     use crate::commands::command::Command;
     use crate::commands::draw::Draw;
+    use crate::snapshot::assert_framebuffer_eq;
 
     #[test]
     fn test_draw_command() {
@@ -60,23 +98,17 @@ mod test {
 
         command.execute(&mut emulator);
 
-        for x in 0..64 {
-            for y in 0..32 {
-                if (3..11).contains(&x) && (2..6).contains(&y) {
-                    assert!(
-                        emulator.display_data[x][y],
-                        "pixel {}, {} not set correctly",
-                        x, y
-                    );
-                } else {
-                    assert!(
-                        !emulator.display_data[x][y],
-                        "pixel {}, {} not set correctly",
-                        x, y
-                    );
-                }
-            }
-        }
+        assert_framebuffer_eq(
+            &emulator.framebuffer,
+            "
+            ...........
+            ...........
+            ...########
+            ...########
+            ...########
+            ...########
+            ",
+        );
     }
 
     #[test]
@@ -87,7 +119,7 @@ mod test {
         emulator.memory[0x202] = 0xFF;
         emulator.memory[0x203] = 0xFF;
         emulator.index_register = 0x200;
-        emulator.display_data[3][2] = true;
+        emulator.framebuffer.set(3, 2, true);
         emulator.registers[0] = 3;
         emulator.registers[1] = 2;
 
@@ -95,31 +127,17 @@ mod test {
 
         command.execute(&mut emulator);
 
-        for x in 0..64 {
-            for y in 0..32 {
-                if (3..11).contains(&x) && (2..6).contains(&y) {
-                    if x == 3 && y == 2 {
-                        assert!(
-                            !emulator.display_data[x][y],
-                            "pixel {}, {} not set correctly",
-                            x, y
-                        );
-                    } else {
-                        assert!(
-                            emulator.display_data[x][y],
-                            "pixel {}, {} not set correctly",
-                            x, y
-                        );
-                    }
-                } else {
-                    assert!(
-                        !emulator.display_data[x][y],
-                        "pixel {}, {} not set correctly",
-                        x, y
-                    );
-                }
-            }
-        }
+        assert_framebuffer_eq(
+            &emulator.framebuffer,
+            "
+            ...........
+            ...........
+            ....#######
+            ...########
+            ...########
+            ...########
+            ",
+        );
         assert_eq!(emulator.registers[0xF], 1);
         assert!(emulator.display_changed);
     }
@@ -139,23 +157,16 @@ mod test {
 
         command.execute(&mut emulator);
 
-        for x in 0..64 {
-            for y in 0..32 {
-                if (2..10).contains(&x) && (1..5).contains(&y) {
-                    assert!(
-                        emulator.display_data[x][y],
-                        "pixel {}, {} not set correctly",
-                        x, y
-                    );
-                } else {
-                    assert!(
-                        !emulator.display_data[x][y],
-                        "pixel {}, {} not set correctly",
-                        x, y
-                    );
-                }
-            }
-        }
+        assert_framebuffer_eq(
+            &emulator.framebuffer,
+            "
+            ..........
+            ..########
+            ..########
+            ..########
+            ..########
+            ",
+        );
         assert!(emulator.display_changed);
     }
 
@@ -174,23 +185,43 @@ mod test {
 
         command.execute(&mut emulator);
 
-        for x in 0..64 {
-            for y in 0..32 {
-                if (62..64).contains(&x) && (30..32).contains(&y) {
-                    assert!(
-                        emulator.display_data[x][y],
-                        "pixel {}, {} not set correctly",
-                        x, y
-                    );
-                } else {
-                    assert!(
-                        !emulator.display_data[x][y],
-                        "pixel {}, {} not set correctly",
-                        x, y
-                    );
-                }
-            }
-        }
+        assert_framebuffer_eq(
+            &emulator.framebuffer,
+            "
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ..............................................................##
+            ..............................................................##
+            ",
+        );
         assert!(emulator.display_changed);
     }
 
@@ -206,24 +237,289 @@ mod test {
 
         command.execute(&mut emulator);
 
-        for x in 0..64 {
-            for y in 0..32 {
-                if y == 0 && (0..4).contains(&x) {
-                    assert!(
-                        emulator.display_data[x][y],
-                        "pixel {}, {} not set correctly",
-                        x, y
-                    );
-                } else {
-                    assert!(
-                        !emulator.display_data[x][y],
-                        "pixel {}, {} not set correctly",
-                        x, y
-                    );
-                }
+        assert_framebuffer_eq(&emulator.framebuffer, "####....");
+        assert!(emulator.display_changed);
+    }
+
+    #[test]
+    fn test_draw_sprite_wraps_without_clipping() {
+        let mut emulator = Chip8::new();
+        emulator.quirks.clipping = false;
+        emulator.memory[0x200] = 0xFF;
+        emulator.memory[0x201] = 0xFF;
+        emulator.memory[0x202] = 0xFF;
+        emulator.memory[0x203] = 0xFF;
+        emulator.index_register = 0x200;
+        emulator.registers[0] = 62;
+        emulator.registers[1] = 30;
+
+        let command = Draw::new(0, 1, 4);
+
+        command.execute(&mut emulator);
+
+        assert_framebuffer_eq(
+            &emulator.framebuffer,
+            "
+            ######........................................................##
+            ######........................................................##
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ................................................................
+            ######........................................................##
+            ######........................................................##
+            ",
+        );
+    }
+
+    #[test]
+    fn test_draw_display_wait() {
+        let mut emulator = Chip8::new();
+        emulator.index_register = 0x200;
+
+        Draw::new(0, 1, 1).execute(&mut emulator);
+        assert!(!emulator.waiting_for_vblank);
+
+        emulator.quirks.display_wait = true;
+        Draw::new(0, 1, 1).execute(&mut emulator);
+        assert!(emulator.waiting_for_vblank);
+    }
+
+    #[test]
+    fn test_draw_no_collision_clears_vf() {
+        let mut emulator = Chip8::new();
+        emulator.memory[0x200] = 0xF0;
+        emulator.index_register = 0x200;
+        emulator.registers[0xF] = 1;
+
+        Draw::new(0, 1, 1).execute(&mut emulator);
+
+        assert_eq!(emulator.registers[0xF], 0);
+    }
+
+    #[test]
+    fn test_draw_blank_sprite_bit_does_not_collide() {
+        let mut emulator = Chip8::new();
+        emulator.memory[0x200] = 0x0F;
+        emulator.index_register = 0x200;
+        emulator.framebuffer.set(0, 0, true);
+
+        Draw::new(0, 1, 1).execute(&mut emulator);
+
+        assert!(emulator.framebuffer.get(0, 0));
+        assert_eq!(emulator.registers[0xF], 0);
+    }
+
+    #[test]
+    fn test_draw_high_res() {
+        let mut emulator = Chip8::new();
+        emulator.framebuffer.set_high_res(true);
+        emulator.memory[0x200] = 0xFF;
+        emulator.index_register = 0x200;
+        emulator.registers[0] = 124;
+        emulator.registers[1] = 63;
+
+        Draw::new(0, 1, 1).execute(&mut emulator);
+
+        for x in 0..128 {
+            for y in 0..64 {
+                assert_eq!(
+                    emulator.framebuffer.get(x, y),
+                    y == 63 && x >= 124,
+                    "pixel {}, {} not set correctly",
+                    x,
+                    y
+                );
             }
         }
-        assert!(emulator.display_changed);
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_draw_large_sprite() {
+        let mut emulator = Chip8::new();
+        emulator.framebuffer.set_high_res(true);
+        for row in 0..16 {
+            emulator.memory[0x300 + row * 2] = 0x80;
+            emulator.memory[0x300 + row * 2 + 1] = 0x01;
+        }
+        emulator.index_register = 0x300;
+        emulator.registers[0] = 10;
+        emulator.registers[1] = 20;
+
+        Draw::new(0, 1, 0).execute(&mut emulator);
+
+        assert_framebuffer_eq(
+            &emulator.framebuffer,
+            "
+            ..........................
+            ..........................
+            ..........................
+            ..........................
+            ..........................
+            ..........................
+            ..........................
+            ..........................
+            ..........................
+            ..........................
+            ..........................
+            ..........................
+            ..........................
+            ..........................
+            ..........................
+            ..........................
+            ..........................
+            ..........................
+            ..........................
+            ..........................
+            ..........#..............#
+            ..........#..............#
+            ..........#..............#
+            ..........#..............#
+            ..........#..............#
+            ..........#..............#
+            ..........#..............#
+            ..........#..............#
+            ..........#..............#
+            ..........#..............#
+            ..........#..............#
+            ..........#..............#
+            ..........#..............#
+            ..........#..............#
+            ..........#..............#
+            ..........#..............#
+            ",
+        );
+        assert_eq!(emulator.registers[0xF], 0);
+    }
+
+    #[test]
+    fn test_draw_large_sprite_low_res() {
+        let mut emulator = Chip8::new();
+        emulator.memory[0x300..0x320].fill(0xFF);
+        emulator.index_register = 0x300;
+        emulator.registers[0] = 56;
+        emulator.registers[1] = 24;
+
+        Draw::new(0, 1, 0).execute(&mut emulator);
+
+        let lit = (0..64)
+            .flat_map(|x| (0..32).map(move |y| (x, y)))
+            .filter(|&(x, y)| emulator.framebuffer.get(x, y))
+            .count();
+        assert_eq!(lit, 8 * 8);
+        assert!(emulator.framebuffer.get(63, 31));
+    }
+
+    #[test]
+    fn test_draw_collision_counts_rows() {
+        let mut emulator = Chip8::new();
+        emulator.quirks.collision_counts_rows = true;
+        emulator.framebuffer.set_high_res(true);
+        emulator.memory[0x300..0x304].fill(0xFF);
+        emulator.index_register = 0x300;
+        emulator.framebuffer.set(0, 1, true);
+        emulator.framebuffer.set(7, 3, true);
+        emulator.framebuffer.set(6, 3, true);
+
+        Draw::new(0, 1, 4).execute(&mut emulator);
+
+        assert_eq!(emulator.registers[0xF], 2);
+
+        // A 16x16 sprite counts its rows the same way
+        emulator.framebuffer.clear();
+        emulator.memory[0x300..0x320].fill(0xFF);
+        for y in 5..10 {
+            emulator.framebuffer.set(15, y, true);
+        }
+
+        Draw::new(0, 1, 0).execute(&mut emulator);
+
+        assert_eq!(emulator.registers[0xF], 5);
+    }
+
+    #[test]
+    fn test_draw_collision_counts_rows_only_in_high_res() {
+        let mut emulator = Chip8::new();
+        emulator.quirks.collision_counts_rows = true;
+        emulator.memory[0x300..0x304].fill(0xFF);
+        emulator.index_register = 0x300;
+        emulator.framebuffer.set(0, 1, true);
+        emulator.framebuffer.set(0, 2, true);
+
+        Draw::new(0, 1, 4).execute(&mut emulator);
+
+        assert_eq!(emulator.registers[0xF], 1);
+    }
+
+    #[test]
+    fn test_draw_planes() {
+        let mut emulator = Chip8::new();
+        emulator.selected_planes = 0b11;
+        // First plane's sprite, then the second's
+        emulator.memory[0x300..0x304].copy_from_slice(&[0xC0, 0xC0, 0xA0, 0xA0]);
+        emulator.index_register = 0x300;
+
+        Draw::new(0, 1, 2).execute(&mut emulator);
+
+        assert_eq!(emulator.framebuffer.colour(0, 0), 3);
+        assert_eq!(emulator.framebuffer.colour(1, 1), 1);
+        assert_eq!(emulator.framebuffer.colour(2, 0), 2);
+        assert_eq!(emulator.framebuffer.colour(3, 0), 0);
+        assert_eq!(emulator.registers[0xF], 0);
+    }
+
+    #[test]
+    fn test_draw_second_plane_only() {
+        let mut emulator = Chip8::new();
+        emulator.framebuffer.set(0, 0, true);
+        emulator.selected_planes = 0b10;
+        emulator.memory[0x300] = 0x80;
+        emulator.index_register = 0x300;
+
+        Draw::new(0, 1, 1).execute(&mut emulator);
+
+        assert_eq!(emulator.framebuffer.colour(0, 0), 3);
+        assert_eq!(emulator.registers[0xF], 0);
+
+        Draw::new(0, 1, 1).execute(&mut emulator);
+
+        assert_eq!(emulator.framebuffer.colour(0, 0), 1);
+        assert_eq!(emulator.registers[0xF], 1);
+    }
+
+    #[test]
+    fn test_draw_no_planes() {
+        let mut emulator = Chip8::new();
+        emulator.selected_planes = 0;
+        emulator.memory[0x300] = 0xFF;
+        emulator.index_register = 0x300;
+
+        Draw::new(0, 1, 1).execute(&mut emulator);
+
+        assert!(!emulator.framebuffer.get(0, 0));
+    }
+}
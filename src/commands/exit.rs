@@ -0,0 +1,38 @@
+use crate::commands::command::Command;
+use crate::run_state::{HaltReason, RunState};
+use crate::Chip8;
+
+pub struct Exit {}
+
+impl Exit {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for Exit {
+    fn execute(&self, emulator: &mut Chip8) {
+        emulator.run_state = RunState::Halted(HaltReason::Exit);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::command::Command;
+    use crate::commands::exit::Exit;
+    use crate::run_state::{HaltReason, RunState};
+    use crate::Chip8;
+
+    #[test]
+    fn test_exit() {
+        let mut emulator = Chip8::new();
+        emulator.load_program(&[0x00, 0xFD, 0x60, 0x01]).unwrap();
+
+        Exit::new().execute(&mut emulator);
+        emulator.step().unwrap();
+
+        assert_eq!(emulator.run_state(), RunState::Halted(HaltReason::Exit));
+        assert_eq!(emulator.registers[0], 0);
+        assert_eq!(emulator.program_counter, 0x200);
+    }
+}
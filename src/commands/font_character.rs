@@ -0,0 +1,114 @@
+use crate::commands::command::Command;
+use crate::fonts::{BIG_FONT_ADDRESS, BIG_FONT_CHARACTER_SIZE, FONT_ADDRESS, FONT_CHARACTER_SIZE};
+use crate::Chip8;
+
+pub struct FontCharacter {
+    register: u8,
+}
+
+impl FontCharacter {
+    pub fn new(register: u8) -> Self {
+        Self { register }
+    }
+}
+
+impl Command for FontCharacter {
+    fn execute(&self, emulator: &mut Chip8) {
+        let character = (emulator.registers[self.register as usize] & 0xF) as u16;
+        emulator.index_register = FONT_ADDRESS + character * FONT_CHARACTER_SIZE;
+    }
+}
+
+pub struct BigFontCharacter {
+    register: u8,
+}
+
+impl BigFontCharacter {
+    pub fn new(register: u8) -> Self {
+        Self { register }
+    }
+}
+
+impl Command for BigFontCharacter {
+    fn execute(&self, emulator: &mut Chip8) {
+        let character = (emulator.registers[self.register as usize] & 0xF) as u16;
+        emulator.index_register = BIG_FONT_ADDRESS + character * BIG_FONT_CHARACTER_SIZE;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::command::Command;
+    use crate::commands::font_character::{BigFontCharacter, FontCharacter};
+    use crate::fonts::{FontSet, BIG_FONT};
+    use crate::{Chip8, Chip8Options};
+
+    #[test]
+    fn test_font_character() {
+        let mut emulator = Chip8::new();
+        emulator.registers[3] = 0xA;
+
+        FontCharacter::new(3).execute(&mut emulator);
+
+        assert_eq!(emulator.index_register, 0x050 + 50);
+        assert_eq!(
+            emulator.memory[0x082..0x087],
+            [0xF0, 0x90, 0xF0, 0x90, 0x90]
+        );
+    }
+
+    #[test]
+    fn test_font_character_uses_low_nibble() {
+        let mut emulator = Chip8::new();
+        emulator.registers[3] = 0x21;
+
+        FontCharacter::new(3).execute(&mut emulator);
+
+        assert_eq!(emulator.index_register, 0x050 + 5);
+    }
+
+    #[test]
+    fn test_font_character_custom_font() {
+        let mut font = [0; 80];
+        font[75..80].copy_from_slice(&[1, 2, 3, 4, 5]);
+        let mut emulator = Chip8::with_options(Chip8Options {
+            font_set: FontSet::Custom(font),
+            ..Default::default()
+        });
+        emulator.registers[0] = 0xF;
+
+        FontCharacter::new(0).execute(&mut emulator);
+
+        let index = emulator.index_register as usize;
+        assert_eq!(emulator.memory[index..index + 5], [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_big_font_character() {
+        let mut emulator = Chip8::new();
+        emulator.registers[2] = 0x8;
+
+        BigFontCharacter::new(2).execute(&mut emulator);
+
+        assert_eq!(emulator.index_register, 0x0A0 + 80);
+        assert_eq!(
+            emulator.memory[0x0F0..0x0FA],
+            [0x3C, 0x7E, 0xC3, 0xC3, 0x7E, 0x7E, 0xC3, 0xC3, 0x7E, 0x3C]
+        );
+    }
+
+    #[test]
+    fn test_big_font_kept_with_custom_font() {
+        let mut emulator = Chip8::with_options(Chip8Options {
+            font_set: FontSet::Custom([0xFF; 80]),
+            ..Default::default()
+        });
+        emulator.registers[0] = 0x0;
+
+        BigFontCharacter::new(0).execute(&mut emulator);
+
+        let index = emulator.index_register as usize;
+        assert_eq!(emulator.memory[index - 1], 0xFF);
+        assert_eq!(emulator.memory[index..index + 10], BIG_FONT[..10]);
+    }
+}
@@ -0,0 +1,37 @@
+use crate::commands::command::Command;
+use crate::Chip8;
+
+pub struct HighResolution {}
+
+impl HighResolution {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for HighResolution {
+    fn execute(&self, emulator: &mut Chip8) {
+        emulator.framebuffer.set_high_res(true);
+        emulator.display_changed = true;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::command::Command;
+    use crate::commands::high_resolution::HighResolution;
+    use crate::Chip8;
+
+    #[test]
+    fn test_high_resolution() {
+        let mut emulator = Chip8::new();
+        emulator.framebuffer.set(10, 10, true);
+
+        HighResolution::new().execute(&mut emulator);
+
+        assert_eq!(emulator.framebuffer.width(), 128);
+        assert_eq!(emulator.framebuffer.height(), 64);
+        assert!(!emulator.framebuffer.get(10, 10));
+        assert!(emulator.display_changed);
+    }
+}
@@ -17,10 +17,33 @@ impl Command for Jump {
     }
 }
 
-mod test{
+pub struct JumpWithOffset {
+    address: u16,
+}
+
+impl JumpWithOffset {
+    pub fn new(address: u16) -> Self {
+        Self { address }
+    }
+}
+
+impl Command for JumpWithOffset {
+    fn execute(&self, emulator: &mut Chip8) {
+        // BNNN adds V0, CHIP-48 read the instruction as BXNN and adds VX instead
+        let register = if emulator.quirks.jump_uses_vx {
+            (self.address >> 8) as usize
+        } else {
+            0
+        };
+        emulator.program_counter = self.address + emulator.registers[register] as u16;
+    }
+}
+
+#[cfg(test)]
+mod test {
     use crate::Chip8;
     use crate::commands::command::Command;
-    use crate::commands::jump::Jump;
+    use crate::commands::jump::{Jump, JumpWithOffset};
 
     #[test]
     fn test_jump() {
@@ -30,4 +53,27 @@ mod test{
 
         assert_eq!(emulator.program_counter, 0x22a);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_jump_with_offset() {
+        let mut emulator = Chip8::new();
+        emulator.registers[0] = 0x10;
+        emulator.registers[2] = 0x30;
+
+        JumpWithOffset::new(0x22A).execute(&mut emulator);
+
+        assert_eq!(emulator.program_counter, 0x23A);
+    }
+
+    #[test]
+    fn test_jump_with_offset_chip48() {
+        let mut emulator = Chip8::new();
+        emulator.quirks.jump_uses_vx = true;
+        emulator.registers[0] = 0x10;
+        emulator.registers[2] = 0x30;
+
+        JumpWithOffset::new(0x22A).execute(&mut emulator);
+
+        assert_eq!(emulator.program_counter, 0x25A);
+    }
+}
@@ -21,6 +21,7 @@ impl Command for Load {
     }
 }
 
+#[cfg(test)]
 mod test {
     use crate::Chip8;
     use crate::commands::command::Command;
@@ -0,0 +1,46 @@
+use crate::audio::PATTERN_SIZE;
+use crate::commands::command::Command;
+use crate::Chip8;
+
+pub struct LoadAudioPattern {}
+
+impl LoadAudioPattern {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for LoadAudioPattern {
+    fn execute(&self, emulator: &mut Chip8) {
+        for i in 0..PATTERN_SIZE {
+            let address = emulator.index_register.wrapping_add(i as u16);
+            emulator.audio_pattern[i] = emulator.read_memory(address);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::audio::DEFAULT_PATTERN;
+    use crate::commands::command::Command;
+    use crate::commands::load_audio_pattern::LoadAudioPattern;
+    use crate::Chip8;
+
+    #[test]
+    fn test_load_audio_pattern() {
+        let mut emulator = Chip8::new();
+        assert_eq!(emulator.audio_pattern, DEFAULT_PATTERN);
+        for i in 0..16 {
+            emulator.memory[0x300 + i] = i as u8;
+        }
+        emulator.index_register = 0x300;
+
+        LoadAudioPattern::new().execute(&mut emulator);
+
+        assert_eq!(
+            emulator.audio_pattern,
+            [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]
+        );
+        assert_eq!(emulator.index_register, 0x300);
+    }
+}
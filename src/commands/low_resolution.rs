@@ -0,0 +1,37 @@
+use crate::commands::command::Command;
+use crate::Chip8;
+
+pub struct LowResolution {}
+
+impl LowResolution {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for LowResolution {
+    fn execute(&self, emulator: &mut Chip8) {
+        emulator.framebuffer.set_high_res(false);
+        emulator.display_changed = true;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::command::Command;
+    use crate::commands::low_resolution::LowResolution;
+    use crate::Chip8;
+
+    #[test]
+    fn test_low_resolution() {
+        let mut emulator = Chip8::new();
+        emulator.framebuffer.set_high_res(true);
+        emulator.framebuffer.set(100, 50, true);
+
+        LowResolution::new().execute(&mut emulator);
+
+        assert_eq!(emulator.framebuffer.width(), 64);
+        assert_eq!(emulator.framebuffer.height(), 32);
+        assert!(emulator.display_changed);
+    }
+}
@@ -19,9 +19,13 @@ impl Command for Or {
     fn execute(&self, emulator: &mut Chip8) {
         emulator.registers[self.register_x as usize] |=
             emulator.registers[self.register_y as usize];
+        if emulator.quirks.vf_reset {
+            emulator.registers[0xF] = 0;
+        }
     }
 }
 
+#[cfg(test)]
 mod test {
     use crate::Chip8;
     use crate::commands::command::Command;
@@ -37,4 +41,18 @@ mod test {
 
         assert_eq!(emulator.registers[0], 0xFE)
     }
+
+    #[test]
+    fn test_bitwise_or_vf_reset() {
+        let mut emulator = Chip8::new();
+        emulator.quirks.vf_reset = true;
+        emulator.registers[0] = 0b10101110;
+        emulator.registers[5] = 0b01010000;
+        emulator.registers[0xF] = 0x55;
+
+        Or::new(0, 5).execute(&mut emulator);
+
+        assert_eq!(emulator.registers[0], 0xFE);
+        assert_eq!(emulator.registers[0xF], 0);
+    }
 }
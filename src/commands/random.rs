@@ -0,0 +1,50 @@
+use crate::commands::command::Command;
+use crate::Chip8;
+
+pub struct Random {
+    register: u8,
+    mask: u8,
+}
+
+impl Random {
+    pub fn new(register: u8, mask: u8) -> Self {
+        Self { register, mask }
+    }
+}
+
+impl Command for Random {
+    fn execute(&self, emulator: &mut Chip8) {
+        emulator.registers[self.register as usize] = emulator.rng.next_u8() & self.mask;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::command::Command;
+    use crate::commands::random::Random;
+    use crate::Chip8;
+
+    #[test]
+    fn test_random_is_masked() {
+        let mut emulator = Chip8::new();
+
+        for _ in 0..100 {
+            Random::new(3, 0x0F).execute(&mut emulator);
+            assert_eq!(emulator.registers[3] & 0xF0, 0);
+        }
+    }
+
+    #[test]
+    fn test_random_is_repeatable_with_seed() {
+        let mut first = Chip8::new();
+        let mut second = Chip8::new();
+        first.set_seed(99);
+        second.set_seed(99);
+
+        for _ in 0..100 {
+            Random::new(0, 0xFF).execute(&mut first);
+            Random::new(0, 0xFF).execute(&mut second);
+            assert_eq!(first.registers[0], second.registers[0]);
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use crate::commands::command::Command;
+use crate::Chip8;
+
+pub struct ReadDelayTimer {
+    register: u8,
+}
+
+impl ReadDelayTimer {
+    pub fn new(register: u8) -> Self {
+        Self { register }
+    }
+}
+
+impl Command for ReadDelayTimer {
+    fn execute(&self, emulator: &mut Chip8) {
+        emulator.registers[self.register as usize] = emulator.delay_timer;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::command::Command;
+    use crate::commands::read_delay_timer::ReadDelayTimer;
+    use crate::Chip8;
+
+    #[test]
+    fn test_read_delay_timer() {
+        let mut emulator = Chip8::new();
+        emulator.delay_timer = 42;
+
+        ReadDelayTimer::new(3).execute(&mut emulator);
+
+        assert_eq!(emulator.registers[3], 42);
+        assert_eq!(emulator.delay_timer, 42);
+    }
+}
@@ -0,0 +1,60 @@
+use crate::commands::command::Command;
+use crate::Chip8;
+
+pub struct ReadFlags {
+    register: u8,
+}
+
+impl ReadFlags {
+    pub fn new(register: u8) -> Self {
+        Self { register }
+    }
+}
+
+impl Command for ReadFlags {
+    fn execute(&self, emulator: &mut Chip8) {
+        let count = (self.register as usize + 1).min(emulator.flag_count());
+        emulator.registers[..count].copy_from_slice(&emulator.flags[..count]);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::command::Command;
+    use crate::commands::read_flags::ReadFlags;
+    use crate::{Chip8, Chip8Options};
+
+    #[test]
+    fn test_read_flags() {
+        let mut emulator = Chip8::new();
+        emulator.set_flags(&[5, 6, 7, 8]);
+
+        ReadFlags::new(1).execute(&mut emulator);
+
+        assert_eq!(emulator.registers[..3], [5, 6, 0]);
+    }
+
+    #[test]
+    fn test_read_flags_xo_chip() {
+        let mut emulator = Chip8::with_options(Chip8Options {
+            xo_chip: true,
+            ..Default::default()
+        });
+        emulator.set_flags(&[3; 16]);
+        emulator.registers[0xF] = 0;
+
+        ReadFlags::new(0xF).execute(&mut emulator);
+
+        assert_eq!(emulator.registers, [3; 16]);
+    }
+
+    #[test]
+    fn test_set_flags_ignores_extra_values() {
+        let mut emulator = Chip8::new();
+
+        emulator.set_flags(&[2; 16]);
+
+        assert_eq!(emulator.flags(), &[2; 8]);
+        assert_eq!(emulator.flags[8..], [0; 8]);
+    }
+}
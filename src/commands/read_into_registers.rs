@@ -14,15 +14,20 @@ impl ReadIntoRegisters {
 impl Command for ReadIntoRegisters {
     fn execute(&self, emulator: &mut Chip8) {
         for i in 0..=(self.register as usize) {
-            emulator.registers[i] = emulator.memory[emulator.index_register as usize + i];
+            let address = emulator.index_register.wrapping_add(i as u16);
+            emulator.registers[i] = emulator.read_memory(address);
         }
+        let increment = emulator.quirks.index_increment.amount(self.register);
+        emulator.index_register = emulator.index_register.wrapping_add(increment);
     }
 }
 
+#[cfg(test)]
 mod test {
-    use crate::Chip8;
     use crate::commands::command::Command;
     use crate::commands::read_into_registers::ReadIntoRegisters;
+    use crate::quirks::IndexIncrement;
+    use crate::{Chip8, Chip8Options};
 
     #[test]
     fn test_command_read_into_registers() {
@@ -39,4 +44,61 @@ mod test {
         assert_eq!(emulator.registers[0], 20);
         assert_eq!(emulator.registers[1], 21);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_command_read_into_registers_increments_index() {
+        let mut emulator = Chip8::new();
+        emulator.quirks.index_increment = IndexIncrement::XPlusOne;
+        emulator.index_register = 0x300;
+        emulator.memory[0x300] = 20;
+        emulator.memory[0x301] = 21;
+
+        ReadIntoRegisters::new(1).execute(&mut emulator);
+
+        assert_eq!(emulator.registers[0], 20);
+        assert_eq!(emulator.registers[1], 21);
+        assert_eq!(emulator.index_register, 0x302);
+    }
+
+    #[test]
+    fn test_command_read_into_registers_increments_index_by_x() {
+        let mut emulator = Chip8::new();
+        emulator.quirks.index_increment = IndexIncrement::X;
+        emulator.index_register = 0x300;
+
+        ReadIntoRegisters::new(1).execute(&mut emulator);
+
+        assert_eq!(emulator.index_register, 0x301);
+    }
+
+    #[test]
+    fn test_command_read_into_registers_wraps_at_end_of_memory() {
+        let mut emulator = Chip8::new();
+        emulator.index_register = 0xFFF;
+        emulator.memory[0xFFF] = 20;
+        emulator.memory[0x000] = 21;
+
+        ReadIntoRegisters::new(1).execute(&mut emulator);
+
+        assert_eq!(emulator.registers[0], 20);
+        assert_eq!(emulator.registers[1], 21);
+    }
+
+    #[test]
+    fn test_command_read_into_registers_wraps_xo_chip() {
+        let mut emulator = Chip8::with_options(Chip8Options {
+            xo_chip: true,
+            ..Default::default()
+        });
+        emulator.quirks.index_increment = IndexIncrement::XPlusOne;
+        emulator.index_register = 0xFFFF;
+        emulator.memory[0xFFFF] = 20;
+        emulator.memory[0x0000] = 21;
+
+        ReadIntoRegisters::new(1).execute(&mut emulator);
+
+        assert_eq!(emulator.registers[0], 20);
+        assert_eq!(emulator.registers[1], 21);
+        assert_eq!(emulator.index_register, 0x0001);
+    }
+}
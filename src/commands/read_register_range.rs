@@ -0,0 +1,59 @@
+use crate::commands::command::Command;
+use crate::Chip8;
+
+pub struct ReadRegisterRange {
+    register_x: u8,
+    register_y: u8,
+}
+
+impl ReadRegisterRange {
+    pub fn new(register_x: u8, register_y: u8) -> Self {
+        Self {
+            register_x,
+            register_y,
+        }
+    }
+}
+
+impl Command for ReadRegisterRange {
+    fn execute(&self, emulator: &mut Chip8) {
+        // VX is always read from I, so X > Y reads the range backwards. I
+        // isn't changed.
+        let (x, y) = (self.register_x, self.register_y);
+        for offset in 0..=x.abs_diff(y) {
+            let register = if x <= y { x + offset } else { x - offset } as usize;
+            let address = emulator.index_register.wrapping_add(offset as u16);
+            emulator.registers[register] = emulator.read_memory(address);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::command::Command;
+    use crate::commands::read_register_range::ReadRegisterRange;
+    use crate::Chip8;
+
+    #[test]
+    fn test_read_register_range() {
+        let mut emulator = Chip8::new();
+        emulator.memory[0x300..0x304].copy_from_slice(&[7, 8, 9, 10]);
+        emulator.index_register = 0x300;
+
+        ReadRegisterRange::new(1, 3).execute(&mut emulator);
+
+        assert_eq!(emulator.registers[..5], [0, 7, 8, 9, 0]);
+        assert_eq!(emulator.index_register, 0x300);
+    }
+
+    #[test]
+    fn test_read_register_range_backwards() {
+        let mut emulator = Chip8::new();
+        emulator.memory[0x300..0x304].copy_from_slice(&[7, 8, 9, 10]);
+        emulator.index_register = 0x300;
+
+        ReadRegisterRange::new(3, 1).execute(&mut emulator);
+
+        assert_eq!(emulator.registers[..5], [0, 9, 8, 7, 0]);
+    }
+}
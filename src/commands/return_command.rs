@@ -1,4 +1,5 @@
 use crate::commands::command::Command;
+use crate::run_state::{HaltReason, RunState};
 use crate::Chip8;
 
 pub struct Return {}
@@ -11,17 +12,22 @@ impl Return {
 
 impl Command for Return {
     fn execute(&self, emulator: &mut Chip8) {
-        emulator.program_counter = emulator
-            .stack
-            .pop()
-            .expect("No value on stack to return to");
+        match emulator.stack.pop() {
+            Some(address) => emulator.program_counter = address,
+            None => {
+                let address = emulator.program_counter.wrapping_sub(2);
+                emulator.run_state = RunState::Halted(HaltReason::StackUnderflow { address });
+            }
+        }
     }
 }
 
+#[cfg(test)]
 mod test {
-    use crate::Chip8;
     use crate::commands::command::Command;
     use crate::commands::return_command::Return;
+    use crate::run_state::{HaltReason, RunState};
+    use crate::{Chip8, Chip8Options};
 
     #[test]
     fn test_return() {
@@ -33,4 +39,34 @@ mod test {
 
         assert_eq!(emulator.program_counter, 0x208);
     }
+
+    #[test]
+    fn test_return_empty_stack() {
+        let mut emulator = Chip8::new();
+        emulator.program_counter = 0x20A;
+
+        Return::new().execute(&mut emulator);
+
+        assert_eq!(
+            emulator.run_state(),
+            RunState::Halted(HaltReason::StackUnderflow { address: 0x208 })
+        );
+    }
+
+    #[test]
+    fn test_return_empty_stack_at_end_of_xo_chip_memory() {
+        let mut emulator = Chip8::with_options(Chip8Options {
+            xo_chip: true,
+            ..Default::default()
+        });
+        emulator.write_memory(0xFFFE, &[0x00, 0xEE]);
+        emulator.program_counter = 0xFFFE;
+
+        emulator.step().unwrap();
+
+        assert_eq!(
+            emulator.run_state(),
+            RunState::Halted(HaltReason::StackUnderflow { address: 0xFFFE })
+        );
+    }
 }
@@ -0,0 +1,164 @@
+use crate::commands::command::Command;
+use crate::Chip8;
+
+pub struct ScrollDown {
+    rows: u8,
+}
+
+impl ScrollDown {
+    pub fn new(rows: u8) -> Self {
+        Self { rows }
+    }
+}
+
+impl Command for ScrollDown {
+    fn execute(&self, emulator: &mut Chip8) {
+        let planes = emulator.selected_planes;
+        // In high resolution both scroll the same distance
+        if emulator.quirks.half_pixel_scroll {
+            emulator
+                .framebuffer
+                .scroll_high_res(0, self.rows as isize, planes);
+        } else {
+            emulator.framebuffer.scroll(0, self.rows as isize, planes);
+        }
+        emulator.display_changed = true;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::command::Command;
+    use crate::commands::scroll_down::ScrollDown;
+    use crate::Chip8;
+
+    fn fill(emulator: &mut Chip8) {
+        for y in 0..emulator.framebuffer.height() {
+            for x in 0..emulator.framebuffer.width() {
+                emulator.framebuffer.set(x, y, true);
+            }
+        }
+    }
+
+    fn lit_rows(emulator: &Chip8) -> Vec<bool> {
+        (0..emulator.framebuffer.height())
+            .map(|y| emulator.framebuffer.get(0, y))
+            .collect()
+    }
+
+    #[test]
+    fn test_scroll_down() {
+        let mut emulator = Chip8::new();
+        emulator.framebuffer.set(5, 0, true);
+        emulator.framebuffer.set(63, 27, true);
+
+        ScrollDown::new(4).execute(&mut emulator);
+
+        assert!(!emulator.framebuffer.get(5, 0));
+        assert!(emulator.framebuffer.get(5, 4));
+        assert!(emulator.framebuffer.get(63, 31));
+        assert!(emulator.display_changed);
+    }
+
+    #[test]
+    fn test_scroll_down_clears_top_rows_and_drops_bottom_rows() {
+        let mut emulator = Chip8::new();
+        fill(&mut emulator);
+        emulator.framebuffer.set(0, 31, false);
+
+        ScrollDown::new(3).execute(&mut emulator);
+
+        let rows = lit_rows(&emulator);
+        assert_eq!(&rows[..3], &[false; 3]);
+        assert!(rows[3..].iter().all(|&lit| lit));
+    }
+
+    #[test]
+    fn test_scroll_down_high_resolution() {
+        let mut emulator = Chip8::new();
+        emulator.framebuffer.set_high_res(true);
+        fill(&mut emulator);
+
+        ScrollDown::new(15).execute(&mut emulator);
+
+        let rows = lit_rows(&emulator);
+        assert_eq!(rows.len(), 64);
+        assert_eq!(&rows[..15], &[false; 15]);
+        assert!(rows[15..].iter().all(|&lit| lit));
+        assert!(emulator.framebuffer.get(127, 63));
+    }
+
+    #[test]
+    fn test_scroll_down_zero_rows() {
+        let mut emulator = Chip8::new();
+        fill(&mut emulator);
+
+        ScrollDown::new(0).execute(&mut emulator);
+
+        assert!(lit_rows(&emulator).iter().all(|&lit| lit));
+    }
+
+    #[test]
+    fn test_scroll_down_one_row_low_resolution() {
+        let mut emulator = Chip8::new();
+        emulator.framebuffer.set(0, 0, true);
+
+        ScrollDown::new(1).execute(&mut emulator);
+
+        assert!(!emulator.framebuffer.get(0, 0));
+        assert!(emulator.framebuffer.get(0, 1));
+    }
+
+    #[test]
+    fn test_scroll_down_selected_planes() {
+        let mut emulator = Chip8::new();
+        emulator.framebuffer.set_colour(3, 0, 0b11);
+        emulator.selected_planes = 0b01;
+
+        ScrollDown::new(1).execute(&mut emulator);
+
+        assert_eq!(emulator.framebuffer.colour(3, 0), 0b10);
+        assert_eq!(emulator.framebuffer.colour(3, 1), 0b01);
+    }
+
+    #[test]
+    fn test_scroll_down_half_pixel_quirk() {
+        let mut emulator = Chip8::new();
+        emulator.quirks.half_pixel_scroll = true;
+        emulator.framebuffer.set(0, 0, true);
+        emulator.framebuffer.set(0, 31, true);
+
+        ScrollDown::new(1).execute(&mut emulator);
+
+        // Half a pixel down: the top pixel now covers rows 1 and 2 of the
+        // 128x64 buffer, and only the top half of the bottom pixel is left
+        let rows: Vec<u8> = (0..4)
+            .map(|y| emulator.framebuffer.high_res_colour(0, y))
+            .collect();
+        assert_eq!(rows, [0, 1, 1, 0]);
+        assert_eq!(emulator.framebuffer.high_res_colour(0, 62), 0);
+        assert_eq!(emulator.framebuffer.high_res_colour(0, 63), 1);
+        assert!(!emulator.framebuffer.get(0, 0));
+        assert!(emulator.framebuffer.get(0, 1));
+        assert!(!emulator.framebuffer.get(0, 31));
+
+        ScrollDown::new(1).execute(&mut emulator);
+
+        assert_eq!(lit_rows(&emulator)[..3], [false, true, false]);
+        assert_eq!(emulator.framebuffer.high_res_colour(0, 63), 0);
+    }
+
+    #[test]
+    fn test_scroll_down_half_pixel_quirk_high_resolution() {
+        let mut emulator = Chip8::new();
+        emulator.quirks.half_pixel_scroll = true;
+        emulator.framebuffer.set_high_res(true);
+        fill(&mut emulator);
+
+        ScrollDown::new(1).execute(&mut emulator);
+
+        let rows = lit_rows(&emulator);
+        assert!(!rows[0]);
+        assert!(rows[1..].iter().all(|&lit| lit));
+    }
+}
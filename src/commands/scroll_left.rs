@@ -0,0 +1,106 @@
+use crate::commands::command::Command;
+use crate::Chip8;
+
+const SCROLL_PIXELS: isize = 4;
+
+pub struct ScrollLeft {}
+
+impl ScrollLeft {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for ScrollLeft {
+    fn execute(&self, emulator: &mut Chip8) {
+        let planes = emulator.selected_planes;
+        // In high resolution both scroll the same distance
+        if emulator.quirks.half_pixel_scroll {
+            emulator
+                .framebuffer
+                .scroll_high_res(-SCROLL_PIXELS, 0, planes);
+        } else {
+            emulator.framebuffer.scroll(-SCROLL_PIXELS, 0, planes);
+        }
+        emulator.display_changed = true;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::command::Command;
+    use crate::commands::scroll_left::ScrollLeft;
+    use crate::Chip8;
+
+    fn fill(emulator: &mut Chip8) {
+        for y in 0..emulator.framebuffer.height() {
+            for x in 0..emulator.framebuffer.width() {
+                emulator.framebuffer.set(x, y, true);
+            }
+        }
+    }
+
+    fn lit_columns(emulator: &Chip8) -> Vec<bool> {
+        (0..emulator.framebuffer.width())
+            .map(|x| emulator.framebuffer.get(x, 0))
+            .collect()
+    }
+
+    #[test]
+    fn test_scroll_left() {
+        let mut emulator = Chip8::new();
+        emulator.framebuffer.set(63, 7, true);
+        emulator.framebuffer.set(4, 31, true);
+
+        ScrollLeft::new().execute(&mut emulator);
+
+        assert!(!emulator.framebuffer.get(63, 7));
+        assert!(emulator.framebuffer.get(59, 7));
+        assert!(emulator.framebuffer.get(0, 31));
+        assert!(emulator.display_changed);
+    }
+
+    #[test]
+    fn test_scroll_left_clears_right_columns_and_drops_left_columns() {
+        let mut emulator = Chip8::new();
+        fill(&mut emulator);
+
+        ScrollLeft::new().execute(&mut emulator);
+
+        let columns = lit_columns(&emulator);
+        assert!(columns[..60].iter().all(|&lit| lit));
+        assert_eq!(&columns[60..], &[false; 4]);
+    }
+
+    #[test]
+    fn test_scroll_left_high_resolution() {
+        let mut emulator = Chip8::new();
+        emulator.framebuffer.set_high_res(true);
+        fill(&mut emulator);
+
+        ScrollLeft::new().execute(&mut emulator);
+
+        let columns = lit_columns(&emulator);
+        assert!(columns[..124].iter().all(|&lit| lit));
+        assert_eq!(&columns[124..], &[false; 4]);
+    }
+
+    #[test]
+    fn test_scroll_left_half_pixel_quirk() {
+        let mut emulator = Chip8::new();
+        emulator.quirks.half_pixel_scroll = true;
+        fill(&mut emulator);
+
+        ScrollLeft::new().execute(&mut emulator);
+
+        // Four high resolution columns are two low resolution ones
+        let columns = lit_columns(&emulator);
+        assert!(columns[..62].iter().all(|&lit| lit));
+        assert_eq!(&columns[62..], &[false; 2]);
+        let edge: Vec<u8> = (123..128)
+            .map(|x| emulator.framebuffer.high_res_colour(x, 0))
+            .collect();
+        assert_eq!(edge, [1, 0, 0, 0, 0]);
+        assert_eq!(emulator.framebuffer.high_res_colour(0, 63), 1);
+    }
+}
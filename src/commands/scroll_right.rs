@@ -0,0 +1,107 @@
+use crate::commands::command::Command;
+use crate::Chip8;
+
+const SCROLL_PIXELS: isize = 4;
+
+pub struct ScrollRight {}
+
+impl ScrollRight {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for ScrollRight {
+    fn execute(&self, emulator: &mut Chip8) {
+        let planes = emulator.selected_planes;
+        // In high resolution both scroll the same distance
+        if emulator.quirks.half_pixel_scroll {
+            emulator
+                .framebuffer
+                .scroll_high_res(SCROLL_PIXELS, 0, planes);
+        } else {
+            emulator.framebuffer.scroll(SCROLL_PIXELS, 0, planes);
+        }
+        emulator.display_changed = true;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::command::Command;
+    use crate::commands::scroll_right::ScrollRight;
+    use crate::Chip8;
+
+    fn fill(emulator: &mut Chip8) {
+        for y in 0..emulator.framebuffer.height() {
+            for x in 0..emulator.framebuffer.width() {
+                emulator.framebuffer.set(x, y, true);
+            }
+        }
+    }
+
+    fn lit_columns(emulator: &Chip8) -> Vec<bool> {
+        (0..emulator.framebuffer.width())
+            .map(|x| emulator.framebuffer.get(x, 0))
+            .collect()
+    }
+
+    #[test]
+    fn test_scroll_right() {
+        let mut emulator = Chip8::new();
+        emulator.framebuffer.set(0, 7, true);
+        emulator.framebuffer.set(59, 31, true);
+
+        ScrollRight::new().execute(&mut emulator);
+
+        assert!(!emulator.framebuffer.get(0, 7));
+        assert!(emulator.framebuffer.get(4, 7));
+        assert!(emulator.framebuffer.get(63, 31));
+        assert!(emulator.display_changed);
+    }
+
+    #[test]
+    fn test_scroll_right_clears_left_columns_and_drops_right_columns() {
+        let mut emulator = Chip8::new();
+        fill(&mut emulator);
+
+        ScrollRight::new().execute(&mut emulator);
+
+        let columns = lit_columns(&emulator);
+        assert_eq!(&columns[..4], &[false; 4]);
+        assert!(columns[4..].iter().all(|&lit| lit));
+    }
+
+    #[test]
+    fn test_scroll_right_high_resolution() {
+        let mut emulator = Chip8::new();
+        emulator.framebuffer.set_high_res(true);
+        fill(&mut emulator);
+
+        ScrollRight::new().execute(&mut emulator);
+
+        let columns = lit_columns(&emulator);
+        assert_eq!(columns.len(), 128);
+        assert_eq!(&columns[..4], &[false; 4]);
+        assert!(columns[4..].iter().all(|&lit| lit));
+    }
+
+    #[test]
+    fn test_scroll_right_half_pixel_quirk() {
+        let mut emulator = Chip8::new();
+        emulator.quirks.half_pixel_scroll = true;
+        fill(&mut emulator);
+
+        ScrollRight::new().execute(&mut emulator);
+
+        // Four high resolution columns are two low resolution ones
+        let columns = lit_columns(&emulator);
+        assert_eq!(&columns[..2], &[false; 2]);
+        assert!(columns[2..].iter().all(|&lit| lit));
+        let edge: Vec<u8> = (0..5)
+            .map(|x| emulator.framebuffer.high_res_colour(x, 0))
+            .collect();
+        assert_eq!(edge, [0, 0, 0, 0, 1]);
+        assert_eq!(emulator.framebuffer.high_res_colour(127, 63), 1);
+    }
+}
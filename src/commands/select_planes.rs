@@ -0,0 +1,36 @@
+use crate::commands::command::Command;
+use crate::framebuffer::ALL_PLANES;
+use crate::Chip8;
+
+pub struct SelectPlanes {
+    planes: u8,
+}
+
+impl SelectPlanes {
+    pub fn new(planes: u8) -> Self {
+        Self { planes }
+    }
+}
+
+impl Command for SelectPlanes {
+    fn execute(&self, emulator: &mut Chip8) {
+        emulator.selected_planes = self.planes & ALL_PLANES;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::command::Command;
+    use crate::commands::select_planes::SelectPlanes;
+    use crate::Chip8;
+
+    #[test]
+    fn test_select_planes() {
+        let mut emulator = Chip8::new();
+        assert_eq!(emulator.selected_planes, 1);
+
+        SelectPlanes::new(3).execute(&mut emulator);
+
+        assert_eq!(emulator.selected_planes, 3);
+    }
+}
@@ -0,0 +1,36 @@
+use crate::commands::command::Command;
+use crate::Chip8;
+
+pub struct SetDelayTimer {
+    register: u8,
+}
+
+impl SetDelayTimer {
+    pub fn new(register: u8) -> Self {
+        Self { register }
+    }
+}
+
+impl Command for SetDelayTimer {
+    fn execute(&self, emulator: &mut Chip8) {
+        emulator.delay_timer = emulator.registers[self.register as usize];
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::command::Command;
+    use crate::commands::set_delay_timer::SetDelayTimer;
+    use crate::Chip8;
+
+    #[test]
+    fn test_set_delay_timer() {
+        let mut emulator = Chip8::new();
+        emulator.registers[7] = 120;
+
+        SetDelayTimer::new(7).execute(&mut emulator);
+
+        assert_eq!(emulator.delay_timer, 120);
+        assert_eq!(emulator.sound_timer, 0);
+    }
+}
@@ -17,6 +17,7 @@ impl Command for SetIndexRegister {
     }
 }
 
+#[cfg(test)]
 mod test {
     use crate::Chip8;
     use crate::commands::command::Command;
@@ -30,4 +31,13 @@ mod test {
 
         assert_eq!(emulator.index_register, 0xFFF);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_set_long_index_register() {
+        let mut emulator = Chip8::new();
+
+        SetIndexRegister::new(0xFEDC).execute(&mut emulator);
+
+        assert_eq!(emulator.index_register, 0xFEDC);
+    }
+}
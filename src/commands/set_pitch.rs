@@ -0,0 +1,36 @@
+use crate::commands::command::Command;
+use crate::Chip8;
+
+pub struct SetPitch {
+    register: u8,
+}
+
+impl SetPitch {
+    pub fn new(register: u8) -> Self {
+        Self { register }
+    }
+}
+
+impl Command for SetPitch {
+    fn execute(&self, emulator: &mut Chip8) {
+        emulator.pitch = emulator.registers[self.register as usize];
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::command::Command;
+    use crate::commands::set_pitch::SetPitch;
+    use crate::Chip8;
+
+    #[test]
+    fn test_set_pitch() {
+        let mut emulator = Chip8::new();
+        assert_eq!(emulator.pitch, 64);
+        emulator.registers[4] = 112;
+
+        SetPitch::new(4).execute(&mut emulator);
+
+        assert_eq!(emulator.pitch, 112);
+    }
+}
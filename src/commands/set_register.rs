@@ -18,6 +18,7 @@ impl Command for SetRegister {
     }
 }
 
+#[cfg(test)]
 mod test {
     use crate::Chip8;
     use crate::commands::command::Command;
@@ -0,0 +1,36 @@
+use crate::commands::command::Command;
+use crate::Chip8;
+
+pub struct SetSoundTimer {
+    register: u8,
+}
+
+impl SetSoundTimer {
+    pub fn new(register: u8) -> Self {
+        Self { register }
+    }
+}
+
+impl Command for SetSoundTimer {
+    fn execute(&self, emulator: &mut Chip8) {
+        emulator.sound_timer = emulator.registers[self.register as usize];
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::command::Command;
+    use crate::commands::set_sound_timer::SetSoundTimer;
+    use crate::Chip8;
+
+    #[test]
+    fn test_set_sound_timer() {
+        let mut emulator = Chip8::new();
+        emulator.registers[0xA] = 15;
+
+        SetSoundTimer::new(0xA).execute(&mut emulator);
+
+        assert_eq!(emulator.sound_timer, 15);
+        assert_eq!(emulator.delay_timer, 0);
+    }
+}
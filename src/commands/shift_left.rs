@@ -17,18 +17,18 @@ impl ShiftLeft {
 
 impl Command for ShiftLeft {
     fn execute(&self, emulator: &mut Chip8) {
-        if emulator.use_old_bit_shift {
+        if emulator.quirks.shift_uses_vy {
             emulator.registers[self.register_x as usize] =
                 emulator.registers[self.register_y as usize] << 1;
             emulator.registers[0xF] = (emulator.registers[self.register_y as usize] & 0x80) >> 7;
         } else {
             emulator.registers[0xF] = (emulator.registers[self.register_x as usize] & 0x80) >> 7;
-            emulator.registers[self.register_x as usize] =
-                emulator.registers[self.register_x as usize] << 1;
+            emulator.registers[self.register_x as usize] <<= 1;
         }
     }
 }
 
+#[cfg(test)]
 mod test {
     use crate::Chip8;
     use crate::commands::command::Command;
@@ -58,7 +58,7 @@ mod test {
     #[test]
     fn test_shift_left_bit_1_vy_used() {
         let mut emulator = Chip8::new();
-        emulator.use_old_bit_shift = true;
+        emulator.quirks.shift_uses_vy = true;
         emulator.registers[5] = 0xFF;
 
         ShiftLeft::new(0, 5).execute(&mut emulator);
@@ -70,7 +70,7 @@ mod test {
     #[test]
     fn test_shift_left_bit_0_vy_used() {
         let mut emulator = Chip8::new();
-        emulator.use_old_bit_shift = true;
+        emulator.quirks.shift_uses_vy = true;
         emulator.registers[5] = 0x7F;
 
         ShiftLeft::new(0, 5).execute(&mut emulator);
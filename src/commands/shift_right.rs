@@ -17,18 +17,18 @@ impl ShiftRight {
 
 impl Command for ShiftRight {
     fn execute(&self, emulator: &mut Chip8) {
-        if emulator.use_old_bit_shift {
+        if emulator.quirks.shift_uses_vy {
             emulator.registers[self.register_x as usize] =
                 emulator.registers[self.register_y as usize] >> 1;
             emulator.registers[0xF] = emulator.registers[self.register_y as usize] & 0b1;
         } else {
             emulator.registers[0xF] = emulator.registers[self.register_x as usize] & 0b1;
-            emulator.registers[self.register_x as usize] =
-                emulator.registers[self.register_x as usize] >> 1;
+            emulator.registers[self.register_x as usize] >>= 1;
         }
     }
 }
 
+#[cfg(test)]
 mod test {
     use crate::Chip8;
     use crate::commands::command::Command;
@@ -57,7 +57,7 @@ mod test {
     #[test]
     fn test_shift_right_bit_0_vy_used() {
         let mut emulator = Chip8::new();
-        emulator.use_old_bit_shift = true;
+        emulator.quirks.shift_uses_vy = true;
         emulator.registers[5] = 0xFE;
 
         ShiftRight::new(0, 5).execute(&mut emulator);
@@ -69,7 +69,7 @@ mod test {
     #[test]
     fn test_shift_right_bit_1_vy_used() {
         let mut emulator = Chip8::new();
-        emulator.use_old_bit_shift = true;
+        emulator.quirks.shift_uses_vy = true;
         emulator.registers[5] = 0xFF;
 
         ShiftRight::new(0, 5).execute(&mut emulator);
@@ -17,11 +17,12 @@ impl SkipEqualX {
 impl Command for SkipEqualX {
     fn execute(&self, emulator: &mut Chip8) {
         if emulator.registers[self.register as usize] == self.check_value {
-            emulator.program_counter += 2
+            emulator.skip_instruction();
         }
     }
 }
 
+#[cfg(test)]
 mod test {
     use crate::Chip8;
     use crate::commands::command::Command;
@@ -20,11 +20,12 @@ impl Command for SkipEqualXY {
         if emulator.registers[self.register_x as usize]
             == emulator.registers[self.register_y as usize]
         {
-            emulator.program_counter += 2
+            emulator.skip_instruction();
         }
     }
 }
 
+#[cfg(test)]
 mod test {
     use crate::Chip8;
     use crate::commands::command::Command;
@@ -0,0 +1,52 @@
+use crate::commands::command::Command;
+use crate::Chip8;
+
+pub struct SkipKeyNotPressed {
+    register: u8,
+}
+
+impl SkipKeyNotPressed {
+    pub fn new(register: u8) -> Self {
+        Self { register }
+    }
+}
+
+impl Command for SkipKeyNotPressed {
+    fn execute(&self, emulator: &mut Chip8) {
+        let key = emulator.registers[self.register as usize] & 0xF;
+        if !emulator.keys[key as usize] {
+            emulator.skip_instruction();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::command::Command;
+    use crate::commands::skip_key_not_pressed::SkipKeyNotPressed;
+    use crate::Chip8;
+
+    #[test]
+    fn test_skip_key_not_pressed() {
+        let mut emulator = Chip8::new();
+        emulator.registers[4] = 0x3;
+        emulator.keys[0x2] = true;
+        emulator.program_counter = 0x200;
+
+        SkipKeyNotPressed::new(4).execute(&mut emulator);
+
+        assert_eq!(emulator.program_counter, 0x202)
+    }
+
+    #[test]
+    fn test_skip_key_not_pressed_pressed() {
+        let mut emulator = Chip8::new();
+        emulator.registers[4] = 0x3;
+        emulator.keys[0x3] = true;
+        emulator.program_counter = 0x200;
+
+        SkipKeyNotPressed::new(4).execute(&mut emulator);
+
+        assert_eq!(emulator.program_counter, 0x200)
+    }
+}
@@ -0,0 +1,52 @@
+use crate::commands::command::Command;
+use crate::Chip8;
+
+pub struct SkipKeyPressed {
+    register: u8,
+}
+
+impl SkipKeyPressed {
+    pub fn new(register: u8) -> Self {
+        Self { register }
+    }
+}
+
+impl Command for SkipKeyPressed {
+    fn execute(&self, emulator: &mut Chip8) {
+        let key = emulator.registers[self.register as usize] & 0xF;
+        if emulator.keys[key as usize] {
+            emulator.skip_instruction();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::command::Command;
+    use crate::commands::skip_key_pressed::SkipKeyPressed;
+    use crate::Chip8;
+
+    #[test]
+    fn test_skip_key_pressed() {
+        let mut emulator = Chip8::new();
+        emulator.registers[2] = 0xB;
+        emulator.keys[0xB] = true;
+        emulator.program_counter = 0x200;
+
+        SkipKeyPressed::new(2).execute(&mut emulator);
+
+        assert_eq!(emulator.program_counter, 0x202)
+    }
+
+    #[test]
+    fn test_skip_key_pressed_not_pressed() {
+        let mut emulator = Chip8::new();
+        emulator.registers[2] = 0xB;
+        emulator.keys[0xA] = true;
+        emulator.program_counter = 0x200;
+
+        SkipKeyPressed::new(2).execute(&mut emulator);
+
+        assert_eq!(emulator.program_counter, 0x200)
+    }
+}
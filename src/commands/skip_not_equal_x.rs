@@ -18,11 +18,12 @@ impl SkipNotEqualX {
 impl Command for SkipNotEqualX {
     fn execute(&self, emulator: &mut Chip8) {
         if emulator.registers[self.register as usize] != self.check_value {
-            emulator.program_counter += 2
+            emulator.skip_instruction();
         }
     }
 }
 
+#[cfg(test)]
 mod test {
     use crate::Chip8;
     use crate::commands::command::Command;
@@ -0,0 +1,61 @@
+use crate::commands::command::Command;
+use crate::Chip8;
+
+pub struct StoreFlags {
+    register: u8,
+}
+
+impl StoreFlags {
+    pub fn new(register: u8) -> Self {
+        Self { register }
+    }
+}
+
+impl Command for StoreFlags {
+    fn execute(&self, emulator: &mut Chip8) {
+        // SUPER-CHIP only has eight flags, so V8 and up have nowhere to go
+        let count = (self.register as usize + 1).min(emulator.flag_count());
+        emulator.flags[..count].copy_from_slice(&emulator.registers[..count]);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::command::Command;
+    use crate::commands::store_flags::StoreFlags;
+    use crate::{Chip8, Chip8Options};
+
+    #[test]
+    fn test_store_flags() {
+        let mut emulator = Chip8::new();
+        emulator.registers[..4].copy_from_slice(&[9, 8, 7, 6]);
+
+        StoreFlags::new(2).execute(&mut emulator);
+
+        assert_eq!(emulator.flags(), &[9, 8, 7, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_store_flags_limited_to_eight() {
+        let mut emulator = Chip8::new();
+        emulator.registers = [1; 16];
+
+        StoreFlags::new(0xF).execute(&mut emulator);
+
+        assert_eq!(emulator.flags(), &[1; 8]);
+        assert_eq!(emulator.flags[8..], [0; 8]);
+    }
+
+    #[test]
+    fn test_store_flags_xo_chip() {
+        let mut emulator = Chip8::with_options(Chip8Options {
+            xo_chip: true,
+            ..Default::default()
+        });
+        emulator.registers = [1; 16];
+
+        StoreFlags::new(0xF).execute(&mut emulator);
+
+        assert_eq!(emulator.flags(), &[1; 16]);
+    }
+}
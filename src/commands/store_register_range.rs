@@ -0,0 +1,70 @@
+use crate::commands::command::Command;
+use crate::Chip8;
+
+pub struct StoreRegisterRange {
+    register_x: u8,
+    register_y: u8,
+}
+
+impl StoreRegisterRange {
+    pub fn new(register_x: u8, register_y: u8) -> Self {
+        Self {
+            register_x,
+            register_y,
+        }
+    }
+}
+
+impl Command for StoreRegisterRange {
+    fn execute(&self, emulator: &mut Chip8) {
+        // VX is always stored at I, so X > Y stores the range backwards. I
+        // isn't changed.
+        let (x, y) = (self.register_x, self.register_y);
+        for offset in 0..=x.abs_diff(y) {
+            let register = if x <= y { x + offset } else { x - offset } as usize;
+            let address = emulator.index_register.wrapping_add(offset as u16);
+            emulator.write_memory_byte(address, emulator.registers[register]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::command::Command;
+    use crate::commands::store_register_range::StoreRegisterRange;
+    use crate::Chip8;
+
+    #[test]
+    fn test_store_register_range() {
+        let mut emulator = Chip8::new();
+        emulator.registers[..6].copy_from_slice(&[1, 2, 3, 4, 5, 6]);
+        emulator.index_register = 0x300;
+
+        StoreRegisterRange::new(2, 4).execute(&mut emulator);
+
+        assert_eq!(emulator.memory[0x300..0x304], [3, 4, 5, 0]);
+        assert_eq!(emulator.index_register, 0x300);
+    }
+
+    #[test]
+    fn test_store_register_range_backwards() {
+        let mut emulator = Chip8::new();
+        emulator.registers[..6].copy_from_slice(&[1, 2, 3, 4, 5, 6]);
+        emulator.index_register = 0x300;
+
+        StoreRegisterRange::new(5, 3).execute(&mut emulator);
+
+        assert_eq!(emulator.memory[0x300..0x304], [6, 5, 4, 0]);
+    }
+
+    #[test]
+    fn test_store_single_register() {
+        let mut emulator = Chip8::new();
+        emulator.registers[7] = 9;
+        emulator.index_register = 0x300;
+
+        StoreRegisterRange::new(7, 7).execute(&mut emulator);
+
+        assert_eq!(emulator.memory[0x300..0x302], [9, 0]);
+    }
+}
@@ -14,15 +14,20 @@ impl StoreRegisters {
 impl Command for StoreRegisters {
     fn execute(&self, emulator: &mut Chip8) {
         for i in 0..=(self.register as usize) {
-            emulator.memory[emulator.index_register as usize + i] = emulator.registers[i];
+            let address = emulator.index_register.wrapping_add(i as u16);
+            emulator.write_memory_byte(address, emulator.registers[i]);
         }
+        let increment = emulator.quirks.index_increment.amount(self.register);
+        emulator.index_register = emulator.index_register.wrapping_add(increment);
     }
 }
 
+#[cfg(test)]
 mod test {
-    use crate::Chip8;
     use crate::commands::command::Command;
     use crate::commands::store_registers::StoreRegisters;
+    use crate::quirks::IndexIncrement;
+    use crate::{Chip8, Chip8Options};
 
     #[test]
     fn test_store_registers_in_memory() {
@@ -48,4 +53,63 @@ mod test {
         assert_eq!(emulator.memory[0x206], 67);
         assert_eq!(emulator.memory[0x207], 88);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_store_registers_increments_index() {
+        let mut emulator = Chip8::new();
+        emulator.quirks.index_increment = IndexIncrement::XPlusOne;
+        emulator.index_register = 0x300;
+        emulator.registers[0] = 1;
+        emulator.registers[1] = 2;
+        emulator.registers[2] = 3;
+
+        StoreRegisters::new(2).execute(&mut emulator);
+
+        assert_eq!(emulator.memory[0x300..0x303], [1, 2, 3]);
+        assert_eq!(emulator.index_register, 0x303);
+    }
+
+    #[test]
+    fn test_store_registers_increments_index_by_x() {
+        let mut emulator = Chip8::new();
+        emulator.quirks.index_increment = IndexIncrement::X;
+        emulator.index_register = 0x300;
+
+        StoreRegisters::new(2).execute(&mut emulator);
+
+        assert_eq!(emulator.index_register, 0x302);
+    }
+
+    #[test]
+    fn test_store_registers_wraps_at_end_of_memory() {
+        let mut emulator = Chip8::new();
+        emulator.quirks.index_increment = IndexIncrement::XPlusOne;
+        emulator.index_register = 0xFFF;
+        emulator.registers[0] = 1;
+        emulator.registers[1] = 2;
+
+        StoreRegisters::new(1).execute(&mut emulator);
+
+        assert_eq!(emulator.memory[0xFFF], 1);
+        assert_eq!(emulator.memory[0x000], 2);
+        assert_eq!(emulator.index_register, 0x1001);
+    }
+
+    #[test]
+    fn test_store_registers_wraps_xo_chip() {
+        let mut emulator = Chip8::with_options(Chip8Options {
+            xo_chip: true,
+            ..Default::default()
+        });
+        emulator.quirks.index_increment = IndexIncrement::XPlusOne;
+        emulator.index_register = 0xFFFF;
+        emulator.registers[0] = 1;
+        emulator.registers[1] = 2;
+
+        StoreRegisters::new(1).execute(&mut emulator);
+
+        assert_eq!(emulator.memory[0xFFFF], 1);
+        assert_eq!(emulator.memory[0x0000], 2);
+        assert_eq!(emulator.index_register, 0x0001);
+    }
+}
@@ -24,6 +24,7 @@ impl Command for Sub {
     }
 }
 
+#[cfg(test)]
 mod test {
     use crate::Chip8;
     use crate::commands::command::Command;
@@ -0,0 +1,100 @@
+use crate::commands::command::Command;
+use crate::run_state::RunState;
+use crate::Chip8;
+
+pub struct WaitForKey {
+    register: u8,
+}
+
+impl WaitForKey {
+    pub fn new(register: u8) -> Self {
+        Self { register }
+    }
+}
+
+impl Command for WaitForKey {
+    fn execute(&self, emulator: &mut Chip8) {
+        // Like the COSMAC VIP, the key only counts once it has been released,
+        // until then the instruction keeps repeating itself.
+        match emulator.waiting_key {
+            Some(key) if !emulator.keys[key as usize] => {
+                emulator.registers[self.register as usize] = key;
+                emulator.waiting_key = None;
+                emulator.run_state = RunState::Running;
+                return;
+            }
+            Some(_) => {}
+            None => {
+                emulator.waiting_key = emulator
+                    .keys
+                    .iter()
+                    .position(|pressed| *pressed)
+                    .map(|key| key as u8);
+            }
+        }
+        emulator.program_counter = emulator.program_counter.wrapping_sub(2);
+        emulator.run_state = RunState::WaitingForKey;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::commands::command::Command;
+    use crate::commands::wait_for_key::WaitForKey;
+    use crate::run_state::RunState;
+    use crate::{Chip8, Chip8Options};
+
+    #[test]
+    fn test_wait_for_key_no_key() {
+        let mut emulator = Chip8::new();
+        emulator.program_counter = 0x202;
+
+        WaitForKey::new(1).execute(&mut emulator);
+
+        assert_eq!(emulator.program_counter, 0x200);
+        assert_eq!(emulator.waiting_key, None);
+        assert_eq!(emulator.run_state(), RunState::WaitingForKey);
+    }
+
+    #[test]
+    fn test_wait_for_key_waits_for_release() {
+        let mut emulator = Chip8::new();
+        emulator.program_counter = 0x202;
+        emulator.keys[0xE] = true;
+
+        WaitForKey::new(1).execute(&mut emulator);
+
+        assert_eq!(emulator.program_counter, 0x200);
+        assert_eq!(emulator.registers[1], 0);
+
+        emulator.program_counter = 0x202;
+        WaitForKey::new(1).execute(&mut emulator);
+
+        assert_eq!(emulator.program_counter, 0x200);
+        assert_eq!(emulator.registers[1], 0);
+
+        emulator.program_counter = 0x202;
+        emulator.keys[0xE] = false;
+        WaitForKey::new(1).execute(&mut emulator);
+
+        assert_eq!(emulator.program_counter, 0x202);
+        assert_eq!(emulator.registers[1], 0xE);
+        assert_eq!(emulator.waiting_key, None);
+        assert_eq!(emulator.run_state(), RunState::Running);
+    }
+
+    #[test]
+    fn test_wait_for_key_at_end_of_xo_chip_memory() {
+        let mut emulator = Chip8::with_options(Chip8Options {
+            xo_chip: true,
+            ..Default::default()
+        });
+        emulator.write_memory(0xFFFE, &[0xF1, 0x0A]);
+        emulator.program_counter = 0xFFFE;
+
+        emulator.step().unwrap();
+
+        assert_eq!(emulator.program_counter, 0xFFFE);
+        assert_eq!(emulator.run_state(), RunState::WaitingForKey);
+    }
+}
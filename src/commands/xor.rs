@@ -19,9 +19,13 @@ impl Command for Xor {
     fn execute(&self, emulator: &mut Chip8) {
         emulator.registers[self.register_x as usize] ^=
             emulator.registers[self.register_y as usize];
+        if emulator.quirks.vf_reset {
+            emulator.registers[0xF] = 0;
+        }
     }
 }
 
+#[cfg(test)]
 mod test {
     use crate::Chip8;
     use crate::commands::command::Command;
@@ -37,4 +41,18 @@ mod test {
 
         assert_eq!(emulator.registers[0], 0b11111110);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_bitwise_xor_vf_reset() {
+        let mut emulator = Chip8::new();
+        emulator.quirks.vf_reset = true;
+        emulator.registers[0] = 0b10101111;
+        emulator.registers[5] = 0b01010001;
+        emulator.registers[0xF] = 0x55;
+
+        Xor::new(0, 5).execute(&mut emulator);
+
+        assert_eq!(emulator.registers[0], 0b11111110);
+        assert_eq!(emulator.registers[0xF], 0);
+    }
+}
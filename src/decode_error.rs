@@ -0,0 +1,72 @@
+use std::{error::Error, fmt};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DecodeErrorKind {
+    UnknownInstruction,
+    /// 0NNN calls a machine code routine on the original hardware, which
+    /// can't be emulated.
+    MachineCodeRoutine,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DecodeError {
+    pub address: u16,
+    pub opcode: u16,
+    pub kind: DecodeErrorKind,
+}
+
+impl DecodeError {
+    pub(crate) fn new(address: u16, command: &[u8], kind: DecodeErrorKind) -> Self {
+        Self {
+            address,
+            opcode: u16::from_be_bytes([command[0], command[1]]),
+            kind,
+        }
+    }
+
+    pub(crate) fn unknown(address: u16, command: &[u8]) -> Self {
+        Self::new(address, command, DecodeErrorKind::UnknownInstruction)
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            DecodeErrorKind::UnknownInstruction => write!(
+                f,
+                "unknown instruction {:04X} at address {:03X}",
+                self.opcode, self.address
+            ),
+            DecodeErrorKind::MachineCodeRoutine => write!(
+                f,
+                "machine code routine {:04X} at address {:03X} can't be run",
+                self.opcode, self.address
+            ),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+#[cfg(test)]
+mod test {
+    use crate::decode_error::{DecodeError, DecodeErrorKind};
+
+    #[test]
+    fn test_decode_error_message() {
+        let error = DecodeError::unknown(0x2A4, &[0xE1, 0x23]);
+
+        assert_eq!(error.opcode, 0xE123);
+        assert_eq!(error.to_string(), "unknown instruction E123 at address 2A4");
+    }
+
+    #[test]
+    fn test_machine_code_error_message() {
+        let error = DecodeError::new(0x200, &[0x01, 0x23], DecodeErrorKind::MachineCodeRoutine);
+
+        assert_eq!(
+            error.to_string(),
+            "machine code routine 0123 at address 200 can't be run"
+        );
+    }
+}
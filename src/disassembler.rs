@@ -0,0 +1,466 @@
+//! Turns a ROM back into Octo assembly.
+//!
+//! Code is found by following every path the program can take from 0x200:
+//! jumps, calls and both sides of each skip. Whatever isn't reached is data.
+//! Bytes a reachable `sprite` draws from, with I set by an earlier `i := NNN`
+//! on the same path, are written one row per line in binary so the picture
+//! can be seen. Jump and call targets and addresses loaded into I get labels.
+//!
+//! Jumps through `jump0` can't be followed past their first entry, so code
+//! only reached through a jump table comes out as data.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use crate::chip8_commands::Chip8Commands;
+use crate::PROGRAM_START;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum LabelKind {
+    Main,
+    Subroutine,
+    Jump,
+    Sprite,
+    Data,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Byte {
+    Data,
+    /// The first byte of an instruction, `length` bytes long.
+    Code(Chip8Commands, u16),
+    /// The rest of an instruction.
+    Operand,
+    /// Drawn by a sprite `width` pixels wide.
+    Sprite(u8),
+}
+
+enum Item {
+    Code(Chip8Commands, u16),
+    Sprite(Vec<u8>),
+    Data(Vec<u8>),
+}
+
+struct Disassembler<'a> {
+    program: &'a [u8],
+    xo_chip: bool,
+    bytes: Vec<Byte>,
+    labels: BTreeMap<u16, LabelKind>,
+}
+
+/// Disassembles `program` as loaded at 0x200. With `xo_chip` F000 NNNN is
+/// read as a four byte instruction.
+pub fn disassemble(program: &[u8], xo_chip: bool) -> String {
+    let mut disassembler = Disassembler {
+        program,
+        xo_chip,
+        bytes: vec![Byte::Data; program.len()],
+        labels: BTreeMap::new(),
+    };
+    disassembler.trace();
+    disassembler.write()
+}
+
+impl Disassembler<'_> {
+    fn offset(&self, address: u16) -> Option<usize> {
+        let offset = (address as usize).checked_sub(PROGRAM_START as usize)?;
+        (offset < self.program.len()).then_some(offset)
+    }
+
+    /// The address just past the program, where a label can still go unless
+    /// the program runs up to the end of memory.
+    fn end(&self) -> Option<u16> {
+        u16::try_from(PROGRAM_START as usize + self.program.len()).ok()
+    }
+
+    fn instruction_length(&self, offset: usize) -> usize {
+        if self.xo_chip && self.program[offset..].starts_with(&[0xF0, 0x00]) {
+            4
+        } else {
+            2
+        }
+    }
+
+    fn add_label(&mut self, address: u16, kind: LabelKind) {
+        self.labels
+            .entry(address)
+            .and_modify(|existing| *existing = (*existing).min(kind))
+            .or_insert(kind);
+    }
+
+    fn trace(&mut self) {
+        self.add_label(PROGRAM_START, LabelKind::Main);
+        // Each path carries the address I was last set to, if it's known
+        let mut pending = vec![(PROGRAM_START, None)];
+        while let Some((mut address, mut index)) = pending.pop() {
+            while let Some(offset) = self.offset(address) {
+                let length = self.instruction_length(offset);
+                let Some(instruction) = self.program.get(offset..offset + length) else {
+                    break;
+                };
+                let Ok(command) = Chip8Commands::new(address, instruction) else {
+                    break;
+                };
+                // Stop at code that's already been traced, or an instruction
+                // that would overlap one
+                if self.bytes[offset..offset + length]
+                    .iter()
+                    .any(|byte| matches!(byte, Byte::Code(..) | Byte::Operand))
+                {
+                    break;
+                }
+                self.bytes[offset] = Byte::Code(command, length as u16);
+                self.bytes[offset + 1..offset + length].fill(Byte::Operand);
+
+                let next = address.wrapping_add(length as u16);
+                match command {
+                    Chip8Commands::Jump(target) => {
+                        self.add_label(target, LabelKind::Jump);
+                        pending.push((target, index));
+                        break;
+                    }
+                    Chip8Commands::JumpWithOffset(target) => {
+                        self.add_label(target, LabelKind::Jump);
+                        pending.push((target, None));
+                        break;
+                    }
+                    Chip8Commands::Call(target) => {
+                        self.add_label(target, LabelKind::Subroutine);
+                        pending.push((target, index));
+                        index = None;
+                    }
+                    Chip8Commands::Return | Chip8Commands::Exit => break,
+                    Chip8Commands::SkipEqualX(..)
+                    | Chip8Commands::SkipNotEqualX(..)
+                    | Chip8Commands::SkipEqualXY(..)
+                    | Chip8Commands::SkipNotEqualXY(..)
+                    | Chip8Commands::SkipKeyPressed(_)
+                    | Chip8Commands::SkipKeyNotPressed(_) => {
+                        let skipped = self
+                            .offset(next)
+                            .map_or(2, |offset| self.instruction_length(offset));
+                        pending.push((next.wrapping_add(skipped as u16), index));
+                    }
+                    Chip8Commands::SetIndexRegister(target) => {
+                        self.add_label(target, LabelKind::Data);
+                        index = Some(target);
+                    }
+                    Chip8Commands::Draw(_, _, rows) => {
+                        if let Some(sprite) = index {
+                            self.mark_sprite(sprite, rows);
+                        }
+                    }
+                    Chip8Commands::AddToIndex(_)
+                    | Chip8Commands::FontCharacter(_)
+                    | Chip8Commands::BigFontCharacter(_)
+                    | Chip8Commands::StoreRegisters(_)
+                    | Chip8Commands::ReadIntoRegisters(_) => index = None,
+                    _ => {}
+                }
+                address = next;
+            }
+        }
+    }
+
+    fn mark_sprite(&mut self, address: u16, rows: u8) {
+        let (width, length) = if rows == 0 {
+            (16, 32)
+        } else {
+            (8, rows as usize)
+        };
+        let Some(start) = self.offset(address) else {
+            return;
+        };
+        self.add_label(address, LabelKind::Sprite);
+        let end = (start + length).min(self.program.len());
+        for byte in &mut self.bytes[start..end] {
+            if let Byte::Data | Byte::Sprite(_) = byte {
+                *byte = Byte::Sprite(width);
+            }
+        }
+    }
+
+    /// Splits the program into lines, breaking data wherever a label falls.
+    fn items(&self) -> Vec<(u16, Item)> {
+        let mut items = Vec::new();
+        let mut offset = 0;
+        while offset < self.program.len() {
+            let address = PROGRAM_START + offset as u16;
+            let labelled =
+                |offset: usize| self.labels.contains_key(&(PROGRAM_START + offset as u16));
+            let (item, length) = match self.bytes[offset] {
+                Byte::Code(command, length) => (Item::Code(command, length), length as usize),
+                Byte::Sprite(width) => {
+                    let length = if width == 16
+                        && self.bytes.get(offset + 1) == Some(&Byte::Sprite(16))
+                        && !labelled(offset + 1)
+                    {
+                        2
+                    } else {
+                        1
+                    };
+                    let row = self.program[offset..offset + length].to_vec();
+                    (Item::Sprite(row), length)
+                }
+                Byte::Data | Byte::Operand => {
+                    let length = (1..8)
+                        .take_while(|length| {
+                            offset + length < self.program.len()
+                                && matches!(self.bytes[offset + length], Byte::Data | Byte::Operand)
+                                && !labelled(offset + length)
+                        })
+                        .count()
+                        + 1;
+                    let data = self.program[offset..offset + length].to_vec();
+                    (Item::Data(data), length)
+                }
+            };
+            items.push((address, item));
+            offset += length;
+        }
+        items
+    }
+
+    fn write(&self) -> String {
+        let items = self.items();
+        // Labels in the middle of an instruction have nowhere to go
+        let mut positions: BTreeSet<u16> = items.iter().map(|(address, _)| *address).collect();
+        positions.extend(self.end());
+        let names: BTreeMap<u16, String> = self
+            .labels
+            .iter()
+            .filter(|(address, _)| positions.contains(address))
+            .map(|(address, kind)| (*address, label_name(*address, *kind)))
+            .collect();
+
+        let mut output = String::new();
+        let write_label = |output: &mut String, address: u16| {
+            if let Some(name) = names.get(&address) {
+                if !output.is_empty() {
+                    output.push('\n');
+                }
+                writeln!(output, ": {}", name).unwrap();
+            }
+        };
+        for (address, item) in &items {
+            write_label(&mut output, *address);
+            let line = match item {
+                Item::Code(command, length) => format_command(*command, *length, &names),
+                Item::Sprite(row) => row
+                    .iter()
+                    .map(|byte| format!("0b{:08b}", byte))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                Item::Data(data) => data
+                    .iter()
+                    .map(|byte| format!("0x{:02X}", byte))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            };
+            writeln!(output, "  {}", line).unwrap();
+        }
+        if let Some(end) = self.end() {
+            write_label(&mut output, end);
+        }
+        output
+    }
+}
+
+fn label_name(address: u16, kind: LabelKind) -> String {
+    let prefix = match kind {
+        LabelKind::Main => return "main".to_string(),
+        LabelKind::Subroutine => "sub",
+        LabelKind::Jump => "label",
+        LabelKind::Sprite => "sprite",
+        LabelKind::Data => "data",
+    };
+    format!("{}-{:03X}", prefix, address)
+}
+
+fn format_command(command: Chip8Commands, length: u16, names: &BTreeMap<u16, String>) -> String {
+    let target = |address: u16| {
+        names
+            .get(&address)
+            .cloned()
+            .unwrap_or_else(|| format!("0x{:03X}", address))
+    };
+    match command {
+        Chip8Commands::ScrollDown(rows) => format!("scroll-down {}", rows),
+        Chip8Commands::ClearScreen => "clear".to_string(),
+        Chip8Commands::Return => "return".to_string(),
+        Chip8Commands::ScrollRight => "scroll-right".to_string(),
+        Chip8Commands::ScrollLeft => "scroll-left".to_string(),
+        Chip8Commands::Exit => "exit".to_string(),
+        Chip8Commands::LowResolution => "lores".to_string(),
+        Chip8Commands::HighResolution => "hires".to_string(),
+        Chip8Commands::Jump(address) => format!("jump {}", target(address)),
+        Chip8Commands::Call(address) => match names.get(&address) {
+            Some(name) => name.clone(),
+            None => format!(":call 0x{:03X}", address),
+        },
+        // Octo's `if ... then` runs the next instruction when the condition
+        // holds, so each skip is written with the opposite comparison
+        Chip8Commands::SkipEqualX(x, value) => format!("if v{:X} != 0x{:02X} then", x, value),
+        Chip8Commands::SkipNotEqualX(x, value) => format!("if v{:X} == 0x{:02X} then", x, value),
+        Chip8Commands::SkipEqualXY(x, y) => format!("if v{:X} != v{:X} then", x, y),
+        Chip8Commands::StoreRegisterRange(x, y) => format!("save v{:X} - v{:X}", x, y),
+        Chip8Commands::ReadRegisterRange(x, y) => format!("load v{:X} - v{:X}", x, y),
+        Chip8Commands::SetRegister(x, value) => format!("v{:X} := 0x{:02X}", x, value),
+        Chip8Commands::AddValueToRegister(x, value) => format!("v{:X} += 0x{:02X}", x, value),
+        Chip8Commands::Load(x, y) => format!("v{:X} := v{:X}", x, y),
+        Chip8Commands::Or(x, y) => format!("v{:X} |= v{:X}", x, y),
+        Chip8Commands::And(x, y) => format!("v{:X} &= v{:X}", x, y),
+        Chip8Commands::Xor(x, y) => format!("v{:X} ^= v{:X}", x, y),
+        Chip8Commands::Add(x, y) => format!("v{:X} += v{:X}", x, y),
+        Chip8Commands::Sub(x, y) => format!("v{:X} -= v{:X}", x, y),
+        Chip8Commands::ShiftRight(x, y) => format!("v{:X} >>= v{:X}", x, y),
+        Chip8Commands::SubN(x, y) => format!("v{:X} =- v{:X}", x, y),
+        Chip8Commands::ShiftLeft(x, y) => format!("v{:X} <<= v{:X}", x, y),
+        Chip8Commands::SkipNotEqualXY(x, y) => format!("if v{:X} == v{:X} then", x, y),
+        // F000 NNNN decodes to the same command as ANNN
+        Chip8Commands::SetIndexRegister(address) if length == 4 => match names.get(&address) {
+            Some(name) => format!("i := long {}", name),
+            None => format!("i := long 0x{:04X}", address),
+        },
+        Chip8Commands::SetIndexRegister(address) => format!("i := {}", target(address)),
+        Chip8Commands::JumpWithOffset(address) => format!("jump0 {}", target(address)),
+        Chip8Commands::Random(x, mask) => format!("v{:X} := random 0x{:02X}", x, mask),
+        Chip8Commands::Draw(x, y, rows) => format!("sprite v{:X} v{:X} {}", x, y, rows),
+        Chip8Commands::SkipKeyPressed(x) => format!("if v{:X} -key then", x),
+        Chip8Commands::SkipKeyNotPressed(x) => format!("if v{:X} key then", x),
+        Chip8Commands::SelectPlanes(planes) => format!("plane {}", planes),
+        Chip8Commands::LoadAudioPattern => "audio".to_string(),
+        Chip8Commands::ReadDelayTimer(x) => format!("v{:X} := delay", x),
+        Chip8Commands::WaitForKey(x) => format!("v{:X} := key", x),
+        Chip8Commands::SetDelayTimer(x) => format!("delay := v{:X}", x),
+        Chip8Commands::SetSoundTimer(x) => format!("buzzer := v{:X}", x),
+        Chip8Commands::AddToIndex(x) => format!("i += v{:X}", x),
+        Chip8Commands::FontCharacter(x) => format!("i := hex v{:X}", x),
+        Chip8Commands::BigFontCharacter(x) => format!("i := bighex v{:X}", x),
+        Chip8Commands::SetPitch(x) => format!("pitch := v{:X}", x),
+        Chip8Commands::BinaryCodedDecimal(x) => format!("bcd v{:X}", x),
+        Chip8Commands::StoreRegisters(x) => format!("save v{:X}", x),
+        Chip8Commands::ReadIntoRegisters(x) => format!("load v{:X}", x),
+        Chip8Commands::StoreFlags(x) => format!("saveflags v{:X}", x),
+        Chip8Commands::ReadFlags(x) => format!("loadflags v{:X}", x),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::disassembler::disassemble;
+
+    #[test]
+    fn test_disassemble_code_and_sprite() {
+        let program = [
+            0x00, 0xE0, // clear
+            0x60, 0x05, // v0 := 5
+            0x22, 0x0C, // call
+            0xA2, 0x0E, // i := sprite
+            0xD0, 0x02, // sprite v0 v0 2
+            0x12, 0x08, // jump back to the sprite
+            0x00, 0xEE, // return, the subroutine
+            0x3C, 0x42, // sprite rows
+            0x12, 0x34, // never reached
+        ];
+
+        assert_eq!(
+            disassemble(&program, false),
+            "\
+: main
+  clear
+  v0 := 0x05
+  sub-20C
+  i := sprite-20E
+
+: label-208
+  sprite v0 v0 2
+  jump label-208
+
+: sub-20C
+  return
+
+: sprite-20E
+  0b00111100
+  0b01000010
+  0x12 0x34
+"
+        );
+    }
+
+    #[test]
+    fn test_disassemble_skips_follow_both_paths() {
+        let program = [
+            0x30, 0x01, // if v0 != 1 then
+            0x12, 0x06, // jump past the data
+            0x00, 0xFD, // exit
+            0x00, 0xE0, // clear, only reached by the jump
+            0x00, 0xFD, // exit
+        ];
+
+        assert_eq!(
+            disassemble(&program, false),
+            "\
+: main
+  if v0 != 0x01 then
+  jump label-206
+  exit
+
+: label-206
+  clear
+  exit
+"
+        );
+    }
+
+    #[test]
+    fn test_disassemble_data_breaks_at_labels() {
+        let program = [
+            0xA2, 0x07, // i := data
+            0x00, 0xFD, // exit
+            0x01, 0x02, 0x03, 0x04, 0x05,
+        ];
+
+        assert_eq!(
+            disassemble(&program, false),
+            "\
+: main
+  i := data-207
+  exit
+  0x01 0x02 0x03
+
+: data-207
+  0x04 0x05
+"
+        );
+    }
+
+    #[test]
+    fn test_disassemble_large_sprite_and_long_index() {
+        let mut program = vec![
+            0xF0, 0x00, 0x02, 0x08, // i := long sprite
+            0xD0, 0x10, // sprite v0 v1 0
+            0x00, 0xFD, // exit
+        ];
+        program.extend([0xFF, 0x00].repeat(16));
+
+        let output = disassemble(&program, true);
+
+        assert!(output.starts_with(": main\n  i := long sprite-208\n"));
+        assert!(output.contains("\n: sprite-208\n  0b11111111 0b00000000\n"));
+        assert_eq!(output.matches("0b11111111 0b00000000").count(), 16);
+
+        // Without XO-CHIP F000 isn't an instruction, so it's all data
+        assert!(disassemble(&program, false).starts_with(": main\n  0xF0 0x00 0x02 0x08"));
+    }
+
+    #[test]
+    fn test_disassemble_target_outside_program() {
+        let program = [0xA0, 0x50, 0x2F, 0x00, 0x1F, 0x00];
+
+        assert_eq!(
+            disassemble(&program, false),
+            ": main\n  i := 0x050\n  :call 0xF00\n  jump 0xF00\n"
+        );
+    }
+}
@@ -2,63 +2,99 @@ use std::io::{self, stdout, Stdout, Write};
 
 use crossterm::{
     cursor,
-    style::{self, Stylize},
+    style::{self, Color, Stylize},
     terminal::{self, LeaveAlternateScreen},
     ExecutableCommand, QueueableCommand,
 };
 
-use super::Display;
+use super::{Display, Renderer, PALETTE};
+use crate::framebuffer::Framebuffer;
 
-pub(crate) struct CrossTermDisplay {
+pub struct CrossTermDisplay {
     stdout: Stdout,
+    renderer: Renderer,
+    size: Option<(usize, usize)>,
 }
 
 impl CrossTermDisplay {
-    pub(crate) fn new() -> CrossTermDisplay {
+    pub fn new() -> CrossTermDisplay {
+        CrossTermDisplay::with_renderer(Renderer::Blocks)
+    }
+
+    pub fn with_renderer(renderer: Renderer) -> CrossTermDisplay {
         let mut stdout = stdout();
         let _ = stdout.execute(terminal::EnterAlternateScreen);
         let _ = stdout.execute(cursor::Hide);
         let _ = stdout.execute(terminal::Clear(terminal::ClearType::All));
-        CrossTermDisplay { stdout }
+        CrossTermDisplay {
+            stdout,
+            renderer,
+            size: None,
+        }
     }
-}
 
-impl Display for CrossTermDisplay {
-    fn draw_display(&mut self, display_data: &[[bool; 32]; 64]) -> Result<(), io::Error> {
-        for y in 0..32 {
-            for x in 0..64 {
-                let x0 = x * 2;
-                let x1 = x0 + 1;
-                if display_data[x][y] {
-                    self.stdout
-                        .queue(cursor::MoveTo(
-                            x0.try_into().unwrap(),
-                            y.try_into().unwrap(),
-                        ))?
-                        .queue(style::PrintStyledContent("█".white()))?
-                        .queue(cursor::MoveTo(
-                            x1.try_into().unwrap(),
-                            y.try_into().unwrap(),
-                        ))?
-                        .queue(style::PrintStyledContent("█".white()))?;
-                } else {
-                    self.stdout
-                        .queue(cursor::MoveTo(
-                            x0.try_into().unwrap(),
-                            y.try_into().unwrap(),
-                        ))?
-                        .queue(style::PrintStyledContent("█".black()))?
-                        .queue(cursor::MoveTo(
-                            x1.try_into().unwrap(),
-                            y.try_into().unwrap(),
-                        ))?
-                        .queue(style::PrintStyledContent("█".black()))?;
-                }
+    fn draw_blocks(&mut self, framebuffer: &Framebuffer) -> Result<(), io::Error> {
+        // Terminal cells are about twice as tall as they are wide, so low
+        // resolution pixels take two cells to keep the screen's proportions
+        let block = if framebuffer.is_high_res() {
+            "█"
+        } else {
+            "██"
+        };
+        for y in 0..framebuffer.height() {
+            self.stdout
+                .queue(cursor::MoveTo(0, y.try_into().unwrap()))?;
+            for x in 0..framebuffer.width() {
+                let colour = colour(framebuffer.colour(x, y));
+                self.stdout
+                    .queue(style::PrintStyledContent(block.with(colour)))?;
             }
         }
         self.stdout.flush()
     }
 
+    fn draw_half_blocks(&mut self, framebuffer: &Framebuffer) -> Result<(), io::Error> {
+        for y in (0..framebuffer.height()).step_by(2) {
+            self.stdout
+                .queue(cursor::MoveTo(0, (y / 2).try_into().unwrap()))?;
+            for x in 0..framebuffer.width() {
+                // The upper half block takes the top pixel's colour and its
+                // background the bottom pixel's
+                let top = colour(framebuffer.colour(x, y));
+                let bottom = colour(framebuffer.colour(x, y + 1));
+                self.stdout
+                    .queue(style::PrintStyledContent("▀".with(top).on(bottom)))?;
+            }
+        }
+        self.stdout.flush()
+    }
+}
+
+fn colour(index: u8) -> Color {
+    let (r, g, b) = PALETTE[index as usize % PALETTE.len()];
+    Color::Rgb { r, g, b }
+}
+
+impl Default for CrossTermDisplay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for CrossTermDisplay {
+    fn draw_display(&mut self, framebuffer: &Framebuffer) -> Result<(), io::Error> {
+        let size = (framebuffer.width(), framebuffer.height());
+        if self.size != Some(size) {
+            self.stdout
+                .queue(terminal::Clear(terminal::ClearType::All))?;
+            self.size = Some(size);
+        }
+        match self.renderer {
+            Renderer::Blocks => self.draw_blocks(framebuffer),
+            Renderer::HalfBlocks => self.draw_half_blocks(framebuffer),
+        }
+    }
+
     fn close_display(&mut self) {
         let _ = self.stdout.execute(LeaveAlternateScreen);
         let _ = self.stdout.execute(cursor::Show);
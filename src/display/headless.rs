@@ -0,0 +1,117 @@
+use std::{cell::RefCell, collections::VecDeque, io, rc::Rc};
+
+use super::Display;
+use crate::framebuffer::Framebuffer;
+
+/// Keeps the frames it is asked to draw instead of showing them, for tests and
+/// machines without a terminal. `new` records every frame, `with_limit` only
+/// the most recent ones.
+///
+/// Clones share their frames, so keep a clone to look at them after handing
+/// the display to `Chip8`.
+#[derive(Clone)]
+pub struct HeadlessDisplay {
+    frames: Rc<RefCell<Frames>>,
+}
+
+struct Frames {
+    kept: VecDeque<Framebuffer>,
+    limit: usize,
+    drawn: usize,
+}
+
+impl HeadlessDisplay {
+    pub fn new() -> HeadlessDisplay {
+        HeadlessDisplay::with_limit(usize::MAX)
+    }
+
+    /// Keeps only the last `limit` frames.
+    pub fn with_limit(limit: usize) -> HeadlessDisplay {
+        HeadlessDisplay {
+            frames: Rc::new(RefCell::new(Frames {
+                kept: VecDeque::new(),
+                limit,
+                drawn: 0,
+            })),
+        }
+    }
+
+    /// The frames still kept, oldest first.
+    pub fn frames(&self) -> Vec<Framebuffer> {
+        self.frames.borrow().kept.iter().cloned().collect()
+    }
+
+    /// How many frames have been drawn, including ones no longer kept.
+    pub fn frame_count(&self) -> usize {
+        self.frames.borrow().drawn
+    }
+
+    pub fn last_frame(&self) -> Option<Framebuffer> {
+        self.frames.borrow().kept.back().cloned()
+    }
+
+    pub fn clear_frames(&self) {
+        let mut frames = self.frames.borrow_mut();
+        frames.kept.clear();
+        frames.drawn = 0;
+    }
+}
+
+impl Default for HeadlessDisplay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for HeadlessDisplay {
+    fn draw_display(&mut self, framebuffer: &Framebuffer) -> Result<(), io::Error> {
+        let mut frames = self.frames.borrow_mut();
+        frames.kept.push_back(framebuffer.clone());
+        if frames.kept.len() > frames.limit {
+            frames.kept.pop_front();
+        }
+        frames.drawn += 1;
+        Ok(())
+    }
+
+    fn close_display(&mut self) {}
+}
+
+#[cfg(test)]
+mod test {
+    use crate::display::{headless::HeadlessDisplay, Display};
+    use crate::framebuffer::Framebuffer;
+
+    #[test]
+    fn test_records_frames() {
+        let display = HeadlessDisplay::new();
+        let mut handed_over = display.clone();
+        let mut framebuffer = Framebuffer::new();
+
+        handed_over.draw_display(&framebuffer).unwrap();
+        framebuffer.set(1, 2, true);
+        handed_over.draw_display(&framebuffer).unwrap();
+
+        assert_eq!(display.frame_count(), 2);
+        assert!(!display.frames()[0].get(1, 2));
+        assert_eq!(display.last_frame(), Some(framebuffer));
+
+        display.clear_frames();
+
+        assert_eq!(display.last_frame(), None);
+    }
+
+    #[test]
+    fn test_keeps_only_the_last_frames() {
+        let display = HeadlessDisplay::with_limit(1);
+        let mut handed_over = display.clone();
+        let mut framebuffer = Framebuffer::new();
+
+        handed_over.draw_display(&framebuffer).unwrap();
+        framebuffer.set(1, 2, true);
+        handed_over.draw_display(&framebuffer).unwrap();
+
+        assert_eq!(display.frame_count(), 2);
+        assert_eq!(display.frames(), vec![framebuffer]);
+    }
+}
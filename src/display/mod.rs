@@ -1,9 +1,56 @@
 use std::io;
 
-pub(crate) mod display;
+use crate::framebuffer::Framebuffer;
 
-pub(crate) trait Display {
-    fn draw_display(&mut self, display_data: &[[bool; 32]; 64]) -> Result<(), io::Error>;
+#[allow(clippy::module_inception)]
+pub mod display;
+pub mod headless;
+
+/// How `CrossTermDisplay` lays pixels out on the terminal.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Renderer {
+    /// Two full block characters per pixel.
+    #[default]
+    Blocks,
+    /// One half block character per pair of pixel rows, for small terminals.
+    HalfBlocks,
+}
+
+impl Renderer {
+    pub fn from_name(name: &str) -> Option<Renderer> {
+        match name {
+            "blocks" => Some(Renderer::Blocks),
+            "half-blocks" => Some(Renderer::HalfBlocks),
+            _ => None,
+        }
+    }
+}
+
+/// RGB colour for each framebuffer colour index. Plain CHIP-8 only uses the
+/// first two, XO-CHIP's two planes the first four.
+pub const PALETTE: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00),
+    (0xFF, 0xFF, 0xFF),
+    (0xAA, 0xAA, 0xAA),
+    (0x55, 0x55, 0x55),
+    (0xFF, 0x00, 0x00),
+    (0x00, 0xFF, 0x00),
+    (0x00, 0x00, 0xFF),
+    (0xFF, 0xFF, 0x00),
+    (0x88, 0x00, 0x00),
+    (0x00, 0x88, 0x00),
+    (0x00, 0x00, 0x88),
+    (0x88, 0x88, 0x00),
+    (0xFF, 0x00, 0xFF),
+    (0x00, 0xFF, 0xFF),
+    (0x88, 0x00, 0x88),
+    (0x00, 0x88, 0x88),
+];
+
+pub trait Display {
+    /// Shows the framebuffer. Implementations read each pixel's colour index
+    /// with `Framebuffer::colour`, 0 being the background.
+    fn draw_display(&mut self, framebuffer: &Framebuffer) -> Result<(), io::Error>;
 
     fn close_display(&mut self);
 }
@@ -0,0 +1,269 @@
+pub const LOW_RES_WIDTH: usize = 64;
+pub const LOW_RES_HEIGHT: usize = 32;
+pub const HIGH_RES_WIDTH: usize = 128;
+pub const HIGH_RES_HEIGHT: usize = 64;
+/// XO-CHIP draws to up to four bitplanes, each pixel's colour index has one
+/// bit per plane.
+pub const PLANE_COUNT: usize = 4;
+pub const ALL_PLANES: u8 = 0b1111;
+
+/// The screen contents, either the 64x32 CHIP-8 resolution or the 128x64
+/// SUPER-CHIP high resolution.
+///
+/// Each pixel is a colour index with bit n set when plane n is lit, so plain
+/// CHIP-8 only ever uses 0 and 1.
+///
+/// Like SUPER-CHIP 1.1, the pixels are always kept at 128x64, with each low
+/// resolution pixel drawn as a 2x2 block. That lets `scroll_high_res` move a
+/// low resolution picture by half a pixel, after which each low resolution
+/// pixel reads as the top left of its block.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Framebuffer {
+    high_res: bool,
+    pixels: Vec<u8>,
+}
+
+impl Framebuffer {
+    pub fn new() -> Framebuffer {
+        Framebuffer {
+            high_res: false,
+            pixels: vec![0; HIGH_RES_WIDTH * HIGH_RES_HEIGHT],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        if self.high_res {
+            HIGH_RES_WIDTH
+        } else {
+            LOW_RES_WIDTH
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        if self.high_res {
+            HIGH_RES_HEIGHT
+        } else {
+            LOW_RES_HEIGHT
+        }
+    }
+
+    pub fn is_high_res(&self) -> bool {
+        self.high_res
+    }
+
+    /// Switches between 64x32 and 128x64, clearing the screen.
+    pub fn set_high_res(&mut self, high_res: bool) {
+        self.high_res = high_res;
+        self.clear();
+    }
+
+    /// Whether the pixel is lit on any plane.
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.colour(x, y) != 0
+    }
+
+    /// Lights the pixel on the first plane only, or clears it on all of them.
+    pub fn set(&mut self, x: usize, y: usize, pixel: bool) {
+        self.set_colour(x, y, pixel as u8);
+    }
+
+    pub fn colour(&self, x: usize, y: usize) -> u8 {
+        self.pixels[self.index(x, y)]
+    }
+
+    pub fn set_colour(&mut self, x: usize, y: usize, colour: u8) {
+        for index in self.block(x, y) {
+            self.pixels[index] = colour & ALL_PLANES;
+        }
+    }
+
+    /// The colour of a pixel of the 128x64 buffer behind the screen, the same
+    /// as `colour` in high resolution.
+    pub fn high_res_colour(&self, x: usize, y: usize) -> u8 {
+        self.pixels[y * HIGH_RES_WIDTH + x]
+    }
+
+    /// XORs a sprite pixel onto one plane, given as a single bit mask,
+    /// returning true if it turned a lit pixel off.
+    pub fn flip(&mut self, x: usize, y: usize, plane: u8) -> bool {
+        let mut collided = false;
+        for index in self.block(x, y) {
+            self.pixels[index] ^= plane;
+            collided |= self.pixels[index] & plane == 0;
+        }
+        collided
+    }
+
+    /// Moves the pixels on `planes` `dx` columns right and `dy` rows down.
+    /// Pixels pushed off the screen are lost and the uncovered area is left
+    /// blank. Other planes stay where they are.
+    pub fn scroll(&mut self, dx: isize, dy: isize, planes: u8) {
+        let scale = if self.high_res { 1 } else { 2 };
+        self.scroll_high_res(dx * scale, dy * scale, planes);
+    }
+
+    /// Like `scroll`, but always in high resolution pixels, so in low
+    /// resolution each step is half a pixel.
+    pub fn scroll_high_res(&mut self, dx: isize, dy: isize, planes: u8) {
+        let (width, height) = (HIGH_RES_WIDTH as isize, HIGH_RES_HEIGHT as isize);
+        let mut scrolled = self.pixels.clone();
+        for y in 0..height {
+            for x in 0..width {
+                let (source_x, source_y) = (x - dx, y - dy);
+                let source = if (0..width).contains(&source_x) && (0..height).contains(&source_y) {
+                    self.pixels[(source_y * width + source_x) as usize]
+                } else {
+                    0
+                };
+                let pixel = &mut scrolled[(y * width + x) as usize];
+                *pixel = (*pixel & !planes) | (source & planes);
+            }
+        }
+        self.pixels = scrolled;
+    }
+
+    pub fn clear(&mut self) {
+        self.pixels.fill(0);
+    }
+
+    /// Clears `planes`, leaving the others as they are.
+    pub fn clear_planes(&mut self, planes: u8) {
+        for pixel in &mut self.pixels {
+            *pixel &= !planes;
+        }
+    }
+
+    /// Indexes of the pixels behind a screen pixel.
+    fn block(&self, x: usize, y: usize) -> impl Iterator<Item = usize> {
+        let top_left = self.index(x, y);
+        let size = if self.high_res { 1 } else { 2 };
+        (0..size).flat_map(move |row| {
+            (0..size).map(move |column| top_left + row * HIGH_RES_WIDTH + column)
+        })
+    }
+
+    /// Index of the top left pixel behind a screen pixel.
+    fn index(&self, x: usize, y: usize) -> usize {
+        assert!(
+            x < self.width() && y < self.height(),
+            "pixel {}, {} is outside the {}x{} screen",
+            x,
+            y,
+            self.width(),
+            self.height()
+        );
+        if self.high_res {
+            y * HIGH_RES_WIDTH + x
+        } else {
+            2 * y * HIGH_RES_WIDTH + 2 * x
+        }
+    }
+}
+
+impl Default for Framebuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::framebuffer::{Framebuffer, ALL_PLANES};
+
+    #[test]
+    fn test_flip_reports_collision() {
+        let mut framebuffer = Framebuffer::new();
+
+        assert!(!framebuffer.flip(3, 4, 1));
+        assert!(framebuffer.get(3, 4));
+        assert!(framebuffer.flip(3, 4, 1));
+        assert!(!framebuffer.get(3, 4));
+    }
+
+    #[test]
+    fn test_flip_planes() {
+        let mut framebuffer = Framebuffer::new();
+
+        assert!(!framebuffer.flip(3, 4, 0b01));
+        assert!(!framebuffer.flip(3, 4, 0b10));
+        assert_eq!(framebuffer.colour(3, 4), 3);
+
+        assert!(framebuffer.flip(3, 4, 0b01));
+        assert_eq!(framebuffer.colour(3, 4), 2);
+        assert!(framebuffer.get(3, 4));
+    }
+
+    #[test]
+    fn test_clear_planes() {
+        let mut framebuffer = Framebuffer::new();
+        framebuffer.set_colour(0, 0, 0b11);
+        framebuffer.set_colour(1, 0, 0b01);
+
+        framebuffer.clear_planes(0b01);
+
+        assert_eq!(framebuffer.colour(0, 0), 0b10);
+        assert_eq!(framebuffer.colour(1, 0), 0);
+    }
+
+    #[test]
+    fn test_switch_resolution() {
+        let mut framebuffer = Framebuffer::new();
+        framebuffer.set(63, 31, true);
+
+        framebuffer.set_high_res(true);
+
+        assert_eq!((framebuffer.width(), framebuffer.height()), (128, 64));
+        assert!(framebuffer.is_high_res());
+        assert!(!framebuffer.get(63, 31));
+        framebuffer.set(127, 63, true);
+
+        framebuffer.set_high_res(false);
+
+        assert_eq!((framebuffer.width(), framebuffer.height()), (64, 32));
+        assert!(!framebuffer.is_high_res());
+    }
+
+    #[test]
+    fn test_scroll() {
+        let mut framebuffer = Framebuffer::new();
+        framebuffer.set(10, 10, true);
+        framebuffer.set(63, 0, true);
+
+        framebuffer.scroll(3, 2, ALL_PLANES);
+
+        assert!(framebuffer.get(13, 12));
+        assert!(!framebuffer.get(10, 10));
+        // Each low resolution pixel is a 2x2 block
+        assert_eq!(
+            framebuffer
+                .pixels
+                .iter()
+                .filter(|pixel| **pixel != 0)
+                .count(),
+            4
+        );
+
+        framebuffer.scroll(-3, -2, ALL_PLANES);
+
+        assert!(framebuffer.get(10, 10));
+        assert_eq!(
+            framebuffer
+                .pixels
+                .iter()
+                .filter(|pixel| **pixel != 0)
+                .count(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_scroll_selected_planes() {
+        let mut framebuffer = Framebuffer::new();
+        framebuffer.set_colour(10, 10, 0b11);
+
+        framebuffer.scroll(0, 1, 0b10);
+
+        assert_eq!(framebuffer.colour(10, 10), 0b01);
+        assert_eq!(framebuffer.colour(10, 11), 0b10);
+    }
+}
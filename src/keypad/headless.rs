@@ -0,0 +1,16 @@
+use std::io;
+
+use super::Keypad;
+
+/// A keypad nobody types on, for tests and machines without a terminal. Keys
+/// only change through `Chip8::set_key`.
+#[derive(Clone, Copy, Default)]
+pub struct HeadlessKeypad;
+
+impl Keypad for HeadlessKeypad {
+    fn update_keys(&mut self, _keys: &mut [bool; 16]) -> Result<(), io::Error> {
+        Ok(())
+    }
+
+    fn close_keypad(&mut self) {}
+}
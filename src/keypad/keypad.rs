@@ -0,0 +1,163 @@
+use std::{
+    io::{self, stdout},
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    event::{
+        self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
+    terminal, ExecutableCommand,
+};
+
+use super::Keypad;
+
+// Terminals without the kitty keyboard protocol never report key releases, so
+// a key counts as released once it hasn't been pressed or repeated for this long.
+const RELEASE_TIMEOUT: Duration = Duration::from_millis(150);
+
+pub struct CrossTermKeypad {
+    opened: bool,
+    reports_releases: bool,
+    last_pressed: [Option<Instant>; 16],
+    quit: bool,
+}
+
+impl CrossTermKeypad {
+    pub fn new() -> CrossTermKeypad {
+        CrossTermKeypad {
+            opened: false,
+            reports_releases: false,
+            last_pressed: [None; 16],
+            quit: false,
+        }
+    }
+
+    fn open(&mut self) -> Result<(), io::Error> {
+        terminal::enable_raw_mode()?;
+        self.reports_releases = terminal::supports_keyboard_enhancement().unwrap_or(false);
+        if self.reports_releases {
+            stdout().execute(PushKeyboardEnhancementFlags(
+                KeyboardEnhancementFlags::REPORT_EVENT_TYPES,
+            ))?;
+        }
+        self.opened = true;
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if key_event.code == KeyCode::Esc
+            || (key_event.code == KeyCode::Char('c')
+                && key_event.modifiers.contains(KeyModifiers::CONTROL))
+        {
+            self.quit = true;
+            return;
+        }
+
+        let KeyCode::Char(character) = key_event.code else {
+            return;
+        };
+        let Some(key) = key_value(character) else {
+            return;
+        };
+
+        self.last_pressed[key as usize] = match key_event.kind {
+            KeyEventKind::Press | KeyEventKind::Repeat => Some(Instant::now()),
+            KeyEventKind::Release => None,
+        };
+    }
+}
+
+impl Default for CrossTermKeypad {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Keypad for CrossTermKeypad {
+    fn update_keys(&mut self, keys: &mut [bool; 16]) -> Result<(), io::Error> {
+        if !self.opened {
+            self.open()?;
+        }
+
+        while event::poll(Duration::ZERO)? {
+            if let Event::Key(key_event) = event::read()? {
+                self.handle_key_event(key_event);
+            }
+        }
+
+        for (key, last_pressed) in keys.iter_mut().zip(self.last_pressed.iter_mut()) {
+            if !self.reports_releases
+                && last_pressed.is_some_and(|pressed| pressed.elapsed() > RELEASE_TIMEOUT)
+            {
+                *last_pressed = None;
+            }
+            *key = last_pressed.is_some();
+        }
+        Ok(())
+    }
+
+    fn quit_requested(&self) -> bool {
+        self.quit
+    }
+
+    fn close_keypad(&mut self) {
+        if self.opened {
+            if self.reports_releases {
+                let _ = stdout().execute(PopKeyboardEnhancementFlags);
+            }
+            let _ = terminal::disable_raw_mode();
+            self.opened = false;
+        }
+    }
+}
+
+/// Maps the left-hand block of a QWERTY keyboard onto the COSMAC VIP keypad:
+///
+/// ```text
+/// 1 2 3 4      1 2 3 C
+/// Q W E R  ->  4 5 6 D
+/// A S D F      7 8 9 E
+/// Z X C V      A 0 B F
+/// ```
+fn key_value(character: char) -> Option<u8> {
+    match character.to_ascii_lowercase() {
+        '1' => Some(0x1),
+        '2' => Some(0x2),
+        '3' => Some(0x3),
+        '4' => Some(0xC),
+        'q' => Some(0x4),
+        'w' => Some(0x5),
+        'e' => Some(0x6),
+        'r' => Some(0xD),
+        'a' => Some(0x7),
+        's' => Some(0x8),
+        'd' => Some(0x9),
+        'f' => Some(0xE),
+        'z' => Some(0xA),
+        'x' => Some(0x0),
+        'c' => Some(0xB),
+        'v' => Some(0xF),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_key_layout() {
+        let layout = "1234qwerasdfzxcv";
+        let expected = [
+            0x1, 0x2, 0x3, 0xC, 0x4, 0x5, 0x6, 0xD, 0x7, 0x8, 0x9, 0xE, 0xA, 0x0, 0xB, 0xF,
+        ];
+
+        for (character, expected) in layout.chars().zip(expected) {
+            assert_eq!(key_value(character), Some(expected));
+            assert_eq!(key_value(character.to_ascii_uppercase()), Some(expected));
+        }
+        assert_eq!(key_value('p'), None);
+    }
+}
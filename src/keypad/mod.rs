@@ -0,0 +1,16 @@
+use std::io;
+
+pub mod headless;
+#[allow(clippy::module_inception)]
+pub mod keypad;
+
+pub trait Keypad {
+    /// Updates `keys` with the state of the 16 hex keys, indexed by key value.
+    fn update_keys(&mut self, keys: &mut [bool; 16]) -> Result<(), io::Error>;
+
+    fn quit_requested(&self) -> bool {
+        false
+    }
+
+    fn close_keypad(&mut self);
+}
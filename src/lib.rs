@@ -0,0 +1,1063 @@
+//! A CHIP-8 interpreter. `Chip8` can be run in real time with `Chip8::start`,
+//! or driven one instruction or frame at a time with `Chip8::step` and
+//! `Chip8::run_frame`. `Chip8::run_state` says whether it is still running
+//! and, once it has halted, why.
+//!
+//! Frames go to the `Display` and keys come from the `Keypad` passed in
+//! `Chip8Options`. `CrossTermDisplay` and `CrossTermKeypad` use the terminal,
+//! while the default `HeadlessDisplay` and `HeadlessKeypad` don't, so the
+//! emulator can run without a TTY.
+
+pub mod assembler;
+pub mod audio;
+pub mod chip8_commands;
+mod commands;
+pub mod decode_error;
+pub mod disassembler;
+pub mod display;
+pub mod fonts;
+pub mod framebuffer;
+pub mod keypad;
+pub mod quirks;
+mod rng;
+pub mod rpl_flags;
+pub mod run_state;
+pub mod snapshot;
+mod timers;
+
+use std::{
+    collections::BTreeSet,
+    error::Error,
+    fmt, io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread, time,
+};
+
+use crate::commands::command::Command;
+use crate::commands::command_parser::parse_command;
+use audio::{
+    Audio, SilentAudio, Synthesizer, DEFAULT_PATTERN, DEFAULT_PITCH, DEFAULT_SAMPLE_RATE,
+    PATTERN_SIZE,
+};
+use decode_error::DecodeError;
+use display::{headless::HeadlessDisplay, Display};
+use fonts::{FontSet, BIG_FONT, BIG_FONT_ADDRESS, FONT_ADDRESS};
+use framebuffer::Framebuffer;
+use keypad::{headless::HeadlessKeypad, Keypad};
+use quirks::Quirks;
+use rng::Rng;
+use rpl_flags::{MAX_FLAG_COUNT, SUPER_CHIP_FLAG_COUNT};
+use run_state::{HaltReason, RunState};
+use timers::{TimerClock, TIMER_FREQUENCY};
+
+pub const PROGRAM_START: u16 = 0x200;
+pub const MEMORY_SIZE: usize = 4096;
+pub const XO_CHIP_MEMORY_SIZE: usize = 65536;
+pub const MAX_PROGRAM_SIZE: usize = MEMORY_SIZE - PROGRAM_START as usize;
+pub const MAX_XO_CHIP_PROGRAM_SIZE: usize = XO_CHIP_MEMORY_SIZE - PROGRAM_START as usize;
+/// Nesting depth of 2NNN calls before the emulator halts with a stack overflow.
+pub const STACK_SIZE: usize = 16;
+
+pub type DebugHook = Box<dyn FnMut(&mut Chip8, &DecodeError)>;
+
+/// What the emulator does when it hits an instruction it can't decode.
+pub enum DecodeErrorPolicy {
+    /// Stop running with `HaltReason::BadOpcode`.
+    Halt,
+    /// Ignore the instruction and carry on with the next one.
+    Skip,
+    /// Hand the emulator to a hook, which can inspect or fix up its state
+    /// before execution carries on.
+    Debug(DebugHook),
+}
+
+/// Returned by `Chip8::load_program` when the program doesn't fit in memory.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ProgramTooLarge {
+    pub size: usize,
+    pub max_size: usize,
+}
+
+impl fmt::Display for ProgramTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "program is {} bytes long, the most that fits in memory is {}",
+            self.size, self.max_size
+        )
+    }
+}
+
+impl Error for ProgramTooLarge {}
+
+/// Settings used to build a `Chip8`.
+pub struct Chip8Options {
+    pub font_set: FontSet,
+    /// Seed for CXNN, taken from the clock when not set.
+    pub seed: Option<u64>,
+    pub instructions_per_second: u32,
+    pub quirks: Quirks,
+    pub decode_error_policy: DecodeErrorPolicy,
+    /// Where frames are drawn. Defaults to a `HeadlessDisplay` that keeps
+    /// the last frame, use a `CrossTermDisplay` to draw in the terminal.
+    pub display: Box<dyn Display>,
+    /// Where key presses come from. Defaults to a `HeadlessKeypad`, use a
+    /// `CrossTermKeypad` to read the terminal.
+    pub keypad: Box<dyn Keypad>,
+    /// Samples per second of the audio from `Chip8::generate_audio`.
+    pub sample_rate: u32,
+    /// Turns on the XO-CHIP extensions: 64KiB of memory, the four byte
+    /// F000 NNNN instruction and sixteen RPL flags instead of eight.
+    pub xo_chip: bool,
+}
+
+impl Default for Chip8Options {
+    fn default() -> Self {
+        Self {
+            font_set: FontSet::Default,
+            seed: None,
+            instructions_per_second: 700,
+            quirks: Quirks::default(),
+            decode_error_policy: DecodeErrorPolicy::Halt,
+            display: Box::new(HeadlessDisplay::with_limit(1)),
+            keypad: Box::new(HeadlessKeypad),
+            sample_rate: DEFAULT_SAMPLE_RATE,
+            xo_chip: false,
+        }
+    }
+}
+
+pub struct Chip8 {
+    pub(crate) memory: Vec<u8>,
+    pub(crate) framebuffer: Framebuffer,
+    pub(crate) program_counter: u16,
+    pub(crate) index_register: u16,
+    pub(crate) stack: Vec<u16>,
+    pub(crate) delay_timer: u8,
+    pub(crate) sound_timer: u8,
+    pub(crate) timer_clock: TimerClock,
+    pub(crate) audio_pattern: [u8; PATTERN_SIZE],
+    pub(crate) pitch: u8,
+    pub(crate) synthesizer: Synthesizer,
+    pub(crate) beeping: bool,
+    // Timer ticks and samples played so far, to work out how many samples
+    // each tick gets when the sample rate isn't a multiple of 60
+    pub(crate) audio_ticks: u64,
+    pub(crate) audio_samples_played: u64,
+    // Reused for every tick's samples
+    pub(crate) audio_buffer: Vec<i16>,
+    pub(crate) registers: [u8; 16],
+    pub(crate) flags: [u8; MAX_FLAG_COUNT],
+    pub(crate) keys: [bool; 16],
+    pub(crate) waiting_key: Option<u8>,
+    pub(crate) rng: Rng,
+    pub(crate) instructions_per_second: u32,
+    pub(crate) display_changed: bool,
+    /// Bit mask of the bitplanes that drawing, clearing and scrolling affect,
+    /// set by FN01.
+    pub(crate) selected_planes: u8,
+    pub(crate) waiting_for_vblank: bool,
+    pub(crate) run_state: RunState,
+    pub(crate) breakpoints: BTreeSet<u16>,
+    // Set by `resume` so the breakpoint just hit doesn't stop it again
+    pub(crate) resuming_from_breakpoint: bool,
+    pub(crate) stop_requested: Arc<AtomicBool>,
+    pub(crate) quirks: Quirks,
+    pub(crate) xo_chip: bool,
+    pub(crate) decode_error_policy: DecodeErrorPolicy,
+    pub(crate) display: Box<dyn Display>,
+    pub(crate) keypad: Box<dyn Keypad>,
+    pub(crate) audio: Box<dyn Audio>,
+}
+
+impl Chip8 {
+    /// An emulator with the default options, which uses a `HeadlessDisplay`
+    /// and `HeadlessKeypad` rather than the terminal.
+    pub fn new() -> Chip8 {
+        Chip8::with_options(Chip8Options::default())
+    }
+
+    pub fn with_options(options: Chip8Options) -> Chip8 {
+        let mut new_chip8 = Chip8 {
+            memory: vec![
+                0;
+                if options.xo_chip {
+                    XO_CHIP_MEMORY_SIZE
+                } else {
+                    MEMORY_SIZE
+                }
+            ],
+            framebuffer: Framebuffer::new(),
+            program_counter: PROGRAM_START,
+            index_register: 0,
+            stack: Vec::new(),
+            delay_timer: 0,
+            sound_timer: 0,
+            timer_clock: TimerClock::new(),
+            audio_pattern: DEFAULT_PATTERN,
+            pitch: DEFAULT_PITCH,
+            synthesizer: Synthesizer::new(options.sample_rate),
+            beeping: false,
+            audio_ticks: 0,
+            audio_samples_played: 0,
+            audio_buffer: Vec::new(),
+            registers: [0; 16],
+            flags: [0; MAX_FLAG_COUNT],
+            keys: [false; 16],
+            waiting_key: None,
+            rng: options.seed.map_or_else(Rng::from_time, Rng::new),
+            instructions_per_second: options.instructions_per_second,
+            display_changed: false,
+            selected_planes: 1,
+            waiting_for_vblank: false,
+            run_state: RunState::Running,
+            breakpoints: BTreeSet::new(),
+            resuming_from_breakpoint: false,
+            stop_requested: Arc::new(AtomicBool::new(false)),
+            quirks: options.quirks,
+            xo_chip: options.xo_chip,
+            decode_error_policy: options.decode_error_policy,
+            display: options.display,
+            keypad: options.keypad,
+            audio: Box::new(SilentAudio),
+        };
+
+        new_chip8.set_fonts(options.font_set);
+        new_chip8
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+
+    pub fn load_program(&mut self, program: &[u8]) -> Result<(), ProgramTooLarge> {
+        let address = PROGRAM_START as usize;
+        if program.len() > self.max_program_size() {
+            return Err(ProgramTooLarge {
+                size: program.len(),
+                max_size: self.max_program_size(),
+            });
+        }
+
+        self.memory[address..address + program.len()].copy_from_slice(program);
+        Ok(())
+    }
+
+    /// Writes `data` to memory from `address`, wrapping round at the end.
+    /// Test ROMs read settings poked in below `PROGRAM_START` this way.
+    pub fn write_memory(&mut self, address: u16, data: &[u8]) {
+        for (offset, byte) in data.iter().enumerate() {
+            let address = address as usize + offset;
+            let length = self.memory.len();
+            self.memory[address % length] = *byte;
+        }
+    }
+
+    pub fn max_program_size(&self) -> usize {
+        self.memory.len() - PROGRAM_START as usize
+    }
+
+    pub fn set_decode_error_policy(&mut self, policy: DecodeErrorPolicy) {
+        self.decode_error_policy = policy;
+    }
+
+    /// Sends the sound to `audio`, which should expect samples at
+    /// `sample_rate`. The sound is thrown away until this is called.
+    pub fn set_audio(&mut self, audio: Box<dyn Audio>) {
+        self.audio = audio;
+    }
+
+    /// Executes a single instruction. Does nothing while paused or halted, or
+    /// while a draw is waiting for the next timer tick under the display wait
+    /// quirk.
+    pub fn step(&mut self) -> Result<(), DecodeError> {
+        if !self.is_running() || self.waiting_for_vblank {
+            return Ok(());
+        }
+        let address = self.program_counter;
+        let resuming = std::mem::take(&mut self.resuming_from_breakpoint);
+        if !resuming && self.breakpoints.contains(&address) {
+            self.run_state = RunState::Halted(HaltReason::Breakpoint(address));
+            return Ok(());
+        }
+        let length = self.instruction_length(address);
+        let mut command = [0; 4];
+        for (offset, byte) in command.iter_mut().enumerate().take(length as usize) {
+            *byte = self.read_memory(address.wrapping_add(offset as u16));
+        }
+        self.program_counter = address.wrapping_add(length);
+        match parse_command(address, &command[..length as usize]) {
+            Ok(decoded_command) => decoded_command.execute(self),
+            Err(error) => self.handle_decode_error(error)?,
+        }
+        self.update_beeping();
+        Ok(())
+    }
+
+    /// Executes one 60 Hz frame worth of instructions, then ticks the timers
+    /// once, then draws the screen if it has changed. Unlike `start` this
+    /// doesn't sleep or poll the keyboard. Nothing happens while paused or
+    /// halted.
+    pub fn run_frame(&mut self) -> Result<(), DecodeError> {
+        if !self.is_running() {
+            return Ok(());
+        }
+        for _ in 0..self.cycles_per_frame() {
+            self.step()?;
+            if self.waiting_for_vblank || self.run_state != RunState::Running {
+                break;
+            }
+        }
+        self.tick_timers(1);
+        self.draw_if_changed();
+        Ok(())
+    }
+
+    /// Reads memory, wrapping round at the end of the address space.
+    pub(crate) fn read_memory(&self, address: u16) -> u8 {
+        self.memory[address as usize % self.memory.len()]
+    }
+
+    /// Writes memory, wrapping round at the end of the address space.
+    pub(crate) fn write_memory_byte(&mut self, address: u16, value: u8) {
+        let length = self.memory.len();
+        self.memory[address as usize % length] = value;
+    }
+
+    /// Under XO-CHIP F000 NNNN is four bytes long, everything else is two.
+    fn instruction_length(&self, address: u16) -> u16 {
+        let opcode = [
+            self.read_memory(address),
+            self.read_memory(address.wrapping_add(1)),
+        ];
+        if self.xo_chip && opcode == [0xF0, 0x00] {
+            4
+        } else {
+            2
+        }
+    }
+
+    /// Moves past the next instruction, for the conditional skips.
+    pub(crate) fn skip_instruction(&mut self) {
+        let length = self.instruction_length(self.program_counter);
+        self.program_counter = self.program_counter.wrapping_add(length);
+    }
+
+    pub fn cycles_per_frame(&self) -> u32 {
+        (self.instructions_per_second / TIMER_FREQUENCY).max(1)
+    }
+
+    pub fn run_state(&self) -> RunState {
+        self.run_state
+    }
+
+    fn is_running(&self) -> bool {
+        matches!(self.run_state, RunState::Running | RunState::WaitingForKey)
+    }
+
+    /// Stops `step` and `run_frame` until `resume` is called. The timers
+    /// stop too.
+    pub fn pause(&mut self) {
+        if self.is_running() {
+            self.run_state = RunState::Paused;
+        }
+    }
+
+    /// Carries on after `pause` or a breakpoint. Other halts are final.
+    pub fn resume(&mut self) {
+        match self.run_state {
+            RunState::Paused => self.run_state = RunState::Running,
+            RunState::Halted(HaltReason::Breakpoint(_)) => {
+                self.run_state = RunState::Running;
+                self.resuming_from_breakpoint = true;
+            }
+            _ => {}
+        }
+    }
+
+    /// Halts with `HaltReason::Breakpoint` before the instruction at `address`
+    /// runs.
+    pub fn add_breakpoint(&mut self, address: u16) {
+        self.breakpoints.insert(address);
+    }
+
+    pub fn remove_breakpoint(&mut self, address: u16) {
+        self.breakpoints.remove(&address);
+    }
+
+    pub fn registers(&self) -> &[u8; 16] {
+        &self.registers
+    }
+
+    pub fn index_register(&self) -> u16 {
+        self.index_register
+    }
+
+    pub fn program_counter(&self) -> u16 {
+        self.program_counter
+    }
+
+    pub fn stack(&self) -> &[u16] {
+        &self.stack
+    }
+
+    /// The RPL user flags written by FX75, eight of them or sixteen under
+    /// XO-CHIP.
+    pub fn flags(&self) -> &[u8] {
+        &self.flags[..self.flag_count()]
+    }
+
+    /// Restores flags saved from an earlier session. Extra values are ignored.
+    pub fn set_flags(&mut self, flags: &[u8]) {
+        let count = flags.len().min(self.flag_count());
+        self.flags[..count].copy_from_slice(&flags[..count]);
+    }
+
+    pub(crate) fn flag_count(&self) -> usize {
+        if self.xo_chip {
+            MAX_FLAG_COUNT
+        } else {
+            SUPER_CHIP_FLAG_COUNT
+        }
+    }
+
+    pub fn delay_timer(&self) -> u8 {
+        self.delay_timer
+    }
+
+    pub fn sound_timer(&self) -> u8 {
+        self.sound_timer
+    }
+
+    /// Fills `samples` with the next stretch of audio: the audio pattern at
+    /// the current pitch while the sound timer is running, silence otherwise.
+    pub fn generate_audio(&mut self, samples: &mut [i16]) {
+        let playing = self.sound_timer > 0;
+        self.synthesizer
+            .generate(&self.audio_pattern, self.pitch, playing, samples);
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.synthesizer.sample_rate()
+    }
+
+    pub fn memory(&self) -> &[u8] {
+        &self.memory
+    }
+
+    pub fn framebuffer(&self) -> &Framebuffer {
+        &self.framebuffer
+    }
+
+    pub fn set_key(&mut self, key: u8, pressed: bool) {
+        self.keys[(key & 0xF) as usize] = pressed;
+    }
+
+    /// A flag that makes `start` return once set, from another thread or a
+    /// Ctrl-C handler. `start` clears it again when it returns.
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        self.stop_requested.clone()
+    }
+
+    /// Runs in real time until the program halts, the user quits or the stop
+    /// flag is set. Quitting, stopping and 00FD return `Ok`, any other halt is
+    /// returned as an error.
+    pub fn start(&mut self) -> Result<(), HaltReason> {
+        let target_ft = time::Duration::from_secs(1) / self.instructions_per_second.max(1);
+        let mut last_timer_update = time::Instant::now();
+        while !self.stop_requested.load(Ordering::SeqCst) && !self.keypad.quit_requested() {
+            let now = time::Instant::now();
+            if self.is_running() {
+                self.update_timers(now.duration_since(last_timer_update));
+            }
+            last_timer_update = now;
+            self.keypad
+                .update_keys(&mut self.keys)
+                .expect("Failed to read keyboard input");
+            // A bad opcode also halts the run state, which is checked below
+            let _ = self.step();
+            self.draw_if_changed();
+            if let RunState::Halted(_) = self.run_state {
+                break;
+            }
+            if let Some(i) = target_ft.checked_sub(now.elapsed()) {
+                thread::sleep(i);
+            }
+        }
+        self.stop_requested.store(false, Ordering::SeqCst);
+        self.keypad.close_keypad();
+        self.display.close_display();
+        self.audio.close_audio();
+        match self.run_state {
+            RunState::Halted(HaltReason::Exit) => Ok(()),
+            RunState::Halted(reason) => Err(reason),
+            _ => Ok(()),
+        }
+    }
+
+    fn draw_if_changed(&mut self) {
+        if self.display_changed {
+            self.display
+                .draw_display(&self.framebuffer)
+                .expect("Failed to draw display");
+            self.display_changed = false;
+        }
+    }
+
+    fn handle_decode_error(&mut self, error: DecodeError) -> Result<(), DecodeError> {
+        match &mut self.decode_error_policy {
+            DecodeErrorPolicy::Halt => {
+                self.run_state = RunState::Halted(HaltReason::BadOpcode(error));
+                Err(error)
+            }
+            DecodeErrorPolicy::Skip => Ok(()),
+            DecodeErrorPolicy::Debug(_) => {
+                // The hook needs the emulator mutably, so take it out while it runs
+                let mut policy =
+                    std::mem::replace(&mut self.decode_error_policy, DecodeErrorPolicy::Halt);
+                if let DecodeErrorPolicy::Debug(hook) = &mut policy {
+                    hook(self, &error);
+                }
+                self.decode_error_policy = policy;
+                Ok(())
+            }
+        }
+    }
+
+    fn update_timers(&mut self, elapsed: time::Duration) {
+        let ticks = self.timer_clock.advance(elapsed);
+        self.tick_timers(ticks);
+    }
+
+    fn tick_timers(&mut self, ticks: u32) {
+        if ticks > 0 {
+            self.waiting_for_vblank = false;
+        }
+        // One tick at a time, so the sound stops on the tick the timer runs out
+        for _ in 0..ticks {
+            self.play_audio_tick();
+            self.delay_timer = self.delay_timer.saturating_sub(1);
+            self.sound_timer = self.sound_timer.saturating_sub(1);
+            self.update_beeping();
+        }
+    }
+
+    fn play_audio_tick(&mut self) {
+        self.audio_ticks += 1;
+        let due = self.audio_ticks * self.sample_rate() as u64 / TIMER_FREQUENCY as u64;
+        let mut samples = std::mem::take(&mut self.audio_buffer);
+        samples.resize((due - self.audio_samples_played) as usize, 0);
+        self.audio_samples_played = due;
+        self.generate_audio(&mut samples);
+        let result = self.audio.play_samples(&samples);
+        self.audio_buffer = samples;
+        if let Err(error) = result {
+            self.drop_audio(error);
+        }
+    }
+
+    fn update_beeping(&mut self) {
+        let beeping = self.sound_timer > 0;
+        if beeping != self.beeping {
+            self.beeping = beeping;
+            if let Err(error) = self.audio.set_beeping(beeping) {
+                self.drop_audio(error);
+            }
+        }
+    }
+
+    /// Carries on without sound once the audio output fails.
+    fn drop_audio(&mut self, error: io::Error) {
+        eprintln!("error: audio output failed, sound is off: {}", error);
+        self.audio.close_audio();
+        self.audio = Box::new(SilentAudio);
+    }
+
+    fn set_fonts(&mut self, font_set: FontSet) {
+        let address = FONT_ADDRESS as usize;
+        self.memory[address..address + 80].copy_from_slice(font_set.data());
+        let address = BIG_FONT_ADDRESS as usize;
+        self.memory[address..address + BIG_FONT.len()].copy_from_slice(&BIG_FONT);
+    }
+}
+
+impl Default for Chip8 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+
+    #[test]
+    fn test_timers_count_down_at_60hz() {
+        let mut emulator = Chip8::new();
+        emulator.delay_timer = 60;
+        emulator.sound_timer = 30;
+
+        emulator.update_timers(time::Duration::from_millis(500));
+
+        assert_eq!(emulator.delay_timer, 30);
+        assert_eq!(emulator.sound_timer, 0);
+    }
+
+    #[test]
+    fn test_timers_stop_at_zero() {
+        let mut emulator = Chip8::new();
+        emulator.delay_timer = 5;
+        emulator.sound_timer = 5;
+
+        emulator.update_timers(time::Duration::from_secs(10));
+
+        assert_eq!(emulator.delay_timer, 0);
+        assert_eq!(emulator.sound_timer, 0);
+    }
+
+    #[test]
+    fn test_bad_opcode_halts() {
+        let mut emulator = Chip8::new();
+        emulator.load_program(&[0xFF, 0xFF]).unwrap();
+
+        let result = emulator.step();
+
+        let error = DecodeError::unknown(0x200, &[0xFF, 0xFF]);
+        assert_eq!(result, Err(error));
+        assert_eq!(
+            emulator.run_state(),
+            RunState::Halted(HaltReason::BadOpcode(error))
+        );
+    }
+
+    #[test]
+    fn test_bad_opcode_skipped() {
+        let mut emulator = Chip8::new();
+        emulator.set_decode_error_policy(DecodeErrorPolicy::Skip);
+        emulator.load_program(&[0xFF, 0xFF, 0x60, 0x2A]).unwrap();
+
+        emulator.step().unwrap();
+        emulator.step().unwrap();
+
+        assert_eq!(emulator.registers[0], 0x2A);
+        assert_eq!(emulator.program_counter, 0x204);
+    }
+
+    #[test]
+    fn test_bad_opcode_debug_hook() {
+        let mut emulator = Chip8::new();
+        emulator.set_decode_error_policy(DecodeErrorPolicy::Debug(Box::new(|emulator, error| {
+            emulator.registers[0xE] = (error.opcode & 0xFF) as u8;
+        })));
+        emulator.load_program(&[0x00, 0x42, 0x60, 0x01]).unwrap();
+
+        emulator.step().unwrap();
+        emulator.step().unwrap();
+
+        assert_eq!(emulator.registers[0xE], 0x42);
+        assert_eq!(emulator.registers[0], 1);
+        assert_eq!(emulator.program_counter, 0x204);
+    }
+
+    #[test]
+    fn test_run_frame() {
+        let mut emulator = Chip8::with_options(Chip8Options {
+            instructions_per_second: 180,
+            ..Default::default()
+        });
+        // V0 += 1 in a loop
+        emulator.load_program(&[0x70, 0x01, 0x12, 0x00]).unwrap();
+        emulator.delay_timer = 10;
+
+        emulator.run_frame().unwrap();
+
+        assert_eq!(emulator.registers()[0], 2);
+        assert_eq!(emulator.program_counter(), 0x202);
+        assert_eq!(emulator.delay_timer(), 9);
+    }
+
+    #[test]
+    fn test_options_seed() {
+        let options = || Chip8Options {
+            seed: Some(7),
+            ..Default::default()
+        };
+        let mut first = Chip8::with_options(options());
+        let mut second = Chip8::with_options(options());
+
+        assert_eq!(first.rng.next_u64(), second.rng.next_u64());
+    }
+
+    #[test]
+    fn test_display_wait_ends_frame() {
+        let mut emulator = Chip8::with_options(Chip8Options {
+            quirks: Quirks::COSMAC_VIP,
+            ..Default::default()
+        });
+        // Draw, then V0 += 1, in a loop
+        emulator
+            .load_program(&[0xD0, 0x01, 0x70, 0x01, 0x12, 0x00])
+            .unwrap();
+
+        emulator.run_frame().unwrap();
+        assert_eq!(emulator.registers()[0], 0);
+        assert_eq!(emulator.program_counter(), 0x202);
+
+        emulator.run_frame().unwrap();
+        assert_eq!(emulator.registers()[0], 1);
+        assert_eq!(emulator.program_counter(), 0x202);
+    }
+
+    #[test]
+    fn test_breakpoint() {
+        let mut emulator = Chip8::new();
+        // V0 += 1 in a loop
+        emulator.load_program(&[0x70, 0x01, 0x12, 0x00]).unwrap();
+        emulator.add_breakpoint(0x202);
+
+        emulator.step().unwrap();
+        emulator.step().unwrap();
+
+        assert_eq!(
+            emulator.run_state(),
+            RunState::Halted(HaltReason::Breakpoint(0x202))
+        );
+        assert_eq!(emulator.program_counter(), 0x202);
+
+        emulator.resume();
+        emulator.step().unwrap();
+        emulator.step().unwrap();
+        emulator.step().unwrap();
+
+        assert_eq!(emulator.registers()[0], 2);
+        assert_eq!(
+            emulator.run_state(),
+            RunState::Halted(HaltReason::Breakpoint(0x202))
+        );
+
+        emulator.remove_breakpoint(0x202);
+        emulator.resume();
+        emulator.run_frame().unwrap();
+
+        assert_eq!(emulator.run_state(), RunState::Running);
+    }
+
+    #[test]
+    fn test_pause() {
+        let mut emulator = Chip8::new();
+        emulator.load_program(&[0x70, 0x01, 0x12, 0x00]).unwrap();
+        emulator.delay_timer = 10;
+
+        emulator.pause();
+        emulator.step().unwrap();
+        emulator.run_frame().unwrap();
+
+        assert_eq!(emulator.run_state(), RunState::Paused);
+        assert_eq!(emulator.registers()[0], 0);
+        assert_eq!(emulator.delay_timer(), 10);
+
+        emulator.resume();
+        emulator.step().unwrap();
+
+        assert_eq!(emulator.run_state(), RunState::Running);
+        assert_eq!(emulator.registers()[0], 1);
+    }
+
+    #[test]
+    fn test_halt_is_final() {
+        let mut emulator = Chip8::new();
+        // Exit, then V0 = 1
+        emulator.load_program(&[0x00, 0xFD, 0x60, 0x01]).unwrap();
+
+        emulator.run_frame().unwrap();
+        emulator.resume();
+        emulator.run_frame().unwrap();
+
+        assert_eq!(emulator.run_state(), RunState::Halted(HaltReason::Exit));
+        assert_eq!(emulator.registers()[0], 0);
+    }
+
+    #[test]
+    fn test_waiting_for_key_keeps_timers_running() {
+        let mut emulator = Chip8::new();
+        // Wait for a key, then loop forever
+        emulator.load_program(&[0xF0, 0x0A, 0x12, 0x02]).unwrap();
+        emulator.delay_timer = 10;
+
+        emulator.run_frame().unwrap();
+
+        assert_eq!(emulator.run_state(), RunState::WaitingForKey);
+        assert_eq!(emulator.delay_timer(), 9);
+
+        emulator.set_key(5, true);
+        emulator.run_frame().unwrap();
+        emulator.set_key(5, false);
+        emulator.run_frame().unwrap();
+
+        assert_eq!(emulator.run_state(), RunState::Running);
+        assert_eq!(emulator.registers()[0], 5);
+    }
+
+    #[test]
+    fn test_load_program_too_large() {
+        let mut emulator = Chip8::new();
+
+        assert_eq!(
+            emulator.load_program(&[0; MAX_PROGRAM_SIZE + 1]),
+            Err(ProgramTooLarge {
+                size: 3585,
+                max_size: 3584
+            })
+        );
+        assert_eq!(emulator.load_program(&[0; MAX_PROGRAM_SIZE]), Ok(()));
+    }
+
+    #[test]
+    fn test_write_memory() {
+        let mut emulator = Chip8::new();
+
+        emulator.write_memory(0x1FF, &[3]);
+        emulator.write_memory(0xFFF, &[1, 2]);
+
+        assert_eq!(emulator.memory()[0x1FF], 3);
+        assert_eq!(emulator.memory()[0xFFF], 1);
+        assert_eq!(emulator.memory()[0x000], 2);
+    }
+
+    #[test]
+    fn test_xo_chip_memory() {
+        let mut emulator = Chip8::with_options(Chip8Options {
+            xo_chip: true,
+            ..Default::default()
+        });
+        let mut program = vec![0; MAX_XO_CHIP_PROGRAM_SIZE];
+        // I = FFF0, then read V0 from there
+        program[..6].copy_from_slice(&[0xF0, 0x00, 0xFF, 0xF0, 0xF0, 0x65]);
+        program[0xFFF0 - PROGRAM_START as usize] = 0x2A;
+
+        emulator.load_program(&program).unwrap();
+        emulator.step().unwrap();
+        emulator.step().unwrap();
+
+        assert_eq!(emulator.memory().len(), 65536);
+        assert_eq!(emulator.max_program_size(), 65024);
+        assert_eq!(emulator.registers()[0], 0x2A);
+    }
+
+    #[test]
+    fn test_long_index_instruction() {
+        let mut emulator = Chip8::with_options(Chip8Options {
+            xo_chip: true,
+            ..Default::default()
+        });
+        emulator
+            .load_program(&[0xF0, 0x00, 0xAB, 0xCD, 0x60, 0x01])
+            .unwrap();
+
+        emulator.step().unwrap();
+        assert_eq!(emulator.index_register(), 0xABCD);
+        assert_eq!(emulator.program_counter(), 0x204);
+
+        emulator.step().unwrap();
+        assert_eq!(emulator.registers()[0], 1);
+    }
+
+    #[test]
+    fn test_long_index_instruction_needs_xo_chip() {
+        let mut emulator = Chip8::new();
+        emulator.load_program(&[0xF0, 0x00, 0xAB, 0xCD]).unwrap();
+
+        assert_eq!(
+            emulator.step(),
+            Err(DecodeError::unknown(0x200, &[0xF0, 0x00]))
+        );
+    }
+
+    #[test]
+    fn test_skip_over_long_instruction() {
+        let mut emulator = Chip8::with_options(Chip8Options {
+            xo_chip: true,
+            ..Default::default()
+        });
+        // Skip if V0 == 0 over F000 NNNN, then V1 = 1
+        emulator
+            .load_program(&[0x30, 0x00, 0xF0, 0x00, 0x12, 0x34, 0x61, 0x01])
+            .unwrap();
+
+        emulator.step().unwrap();
+        assert_eq!(emulator.program_counter(), 0x206);
+
+        emulator.step().unwrap();
+        assert_eq!(emulator.registers()[1], 1);
+        assert_eq!(emulator.index_register(), 0);
+    }
+
+    #[test]
+    fn test_generate_audio() {
+        let mut emulator = Chip8::with_options(Chip8Options {
+            sample_rate: 8000,
+            ..Default::default()
+        });
+        // Load an all ones pattern and set the sound timer
+        emulator.memory[0x300..0x310].fill(0xFF);
+        emulator
+            .load_program(&[0xA3, 0x00, 0xF0, 0x02, 0x60, 0x02, 0xF0, 0x18])
+            .unwrap();
+        let mut samples = [0; 4];
+
+        emulator.generate_audio(&mut samples);
+        assert_eq!(samples, [0; 4]);
+
+        for _ in 0..4 {
+            emulator.step().unwrap();
+        }
+        emulator.generate_audio(&mut samples);
+
+        assert_eq!(emulator.sample_rate(), 8000);
+        assert!(samples.iter().all(|sample| *sample > 0));
+    }
+
+    #[derive(Default)]
+    struct RecordedAudio {
+        beeps: Vec<bool>,
+        samples: Vec<Vec<i16>>,
+    }
+
+    struct RecordingAudio(Rc<RefCell<RecordedAudio>>);
+
+    impl Audio for RecordingAudio {
+        fn set_beeping(&mut self, beeping: bool) -> Result<(), std::io::Error> {
+            self.0.borrow_mut().beeps.push(beeping);
+            Ok(())
+        }
+
+        fn play_samples(&mut self, samples: &[i16]) -> Result<(), std::io::Error> {
+            self.0.borrow_mut().samples.push(samples.to_vec());
+            Ok(())
+        }
+
+        fn close_audio(&mut self) {}
+    }
+
+    #[test]
+    fn test_audio_follows_sound_timer() {
+        let mut emulator = Chip8::with_options(Chip8Options {
+            sample_rate: 22050,
+            ..Default::default()
+        });
+        let recorded = Rc::new(RefCell::new(RecordedAudio::default()));
+        emulator.set_audio(Box::new(RecordingAudio(recorded.clone())));
+        // Sound timer = 2, then loop
+        emulator
+            .load_program(&[0x60, 0x02, 0xF0, 0x18, 0x12, 0x04])
+            .unwrap();
+
+        emulator.step().unwrap();
+        emulator.step().unwrap();
+        assert_eq!(recorded.borrow().beeps, [true]);
+
+        for _ in 0..4 {
+            emulator.run_frame().unwrap();
+        }
+
+        let recorded = recorded.borrow();
+        assert_eq!(recorded.beeps, [true, false]);
+        // 22050 / 60 is 367.5 samples a tick
+        let lengths: Vec<usize> = recorded.samples.iter().map(Vec::len).collect();
+        assert_eq!(lengths, [367, 368, 367, 368]);
+        let sounding: Vec<bool> = recorded
+            .samples
+            .iter()
+            .map(|samples| samples.iter().any(|sample| *sample != 0))
+            .collect();
+        assert_eq!(sounding, [true, true, false, false]);
+    }
+
+    struct FailingAudio(Rc<RefCell<u32>>);
+
+    impl Audio for FailingAudio {
+        fn play_samples(&mut self, _samples: &[i16]) -> Result<(), std::io::Error> {
+            *self.0.borrow_mut() += 1;
+            Err(std::io::Error::other("disk full"))
+        }
+
+        fn close_audio(&mut self) {}
+    }
+
+    #[test]
+    fn test_failing_audio_is_dropped() {
+        let mut emulator = Chip8::new();
+        let calls = Rc::new(RefCell::new(0));
+        emulator.set_audio(Box::new(FailingAudio(calls.clone())));
+        emulator.load_program(&[0x12, 0x00]).unwrap();
+
+        emulator.run_frame().unwrap();
+        emulator.run_frame().unwrap();
+
+        assert_eq!(*calls.borrow(), 1);
+    }
+
+    #[test]
+    fn test_run_frame_draws_changed_frames() {
+        let display = HeadlessDisplay::new();
+        let mut emulator = Chip8::with_options(Chip8Options {
+            display: Box::new(display.clone()),
+            ..Default::default()
+        });
+        // Draw the 0 glyph, then loop
+        emulator
+            .load_program(&[0xA0, 0x50, 0xD0, 0x05, 0x12, 0x04])
+            .unwrap();
+
+        emulator.run_frame().unwrap();
+        emulator.run_frame().unwrap();
+
+        assert_eq!(display.frame_count(), 1);
+        let frame = display.last_frame().unwrap();
+        assert_eq!(&frame, emulator.framebuffer());
+        assert!(frame.get(0, 0) && frame.get(3, 4) && !frame.get(1, 1));
+    }
+
+    #[test]
+    fn test_start_runs_without_a_terminal() {
+        let mut emulator = Chip8::with_options(Chip8Options {
+            instructions_per_second: 1000 * 60,
+            ..Default::default()
+        });
+        emulator.set_key(0x5, true);
+        // Skip the exit unless key 5 is held, then fall through to 00FD
+        emulator
+            .load_program(&[0x60, 0x05, 0xE0, 0xA1, 0x00, 0xFD, 0x12, 0x04])
+            .unwrap();
+
+        assert_eq!(emulator.start(), Ok(()));
+        assert_eq!(emulator.run_state(), RunState::Halted(HaltReason::Exit));
+    }
+
+    #[test]
+    fn test_stop_flag_ends_start() {
+        let mut emulator = Chip8::new();
+        // Loop forever
+        emulator.load_program(&[0x12, 0x00]).unwrap();
+        emulator.stop_flag().store(true, Ordering::SeqCst);
+
+        assert_eq!(emulator.start(), Ok(()));
+        assert!(emulator.is_running());
+        assert!(!emulator.stop_flag().load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_start_twice() {
+        for _ in 0..2 {
+            let mut emulator = Chip8::new();
+            emulator.load_program(&[0x00, 0xFD]).unwrap();
+
+            assert_eq!(emulator.start(), Ok(()));
+        }
+    }
+}
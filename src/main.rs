@@ -1,138 +1,429 @@
-mod chip8_commands;
-mod commands;
-mod display;
-
-use std::{
-    fs::File,
-    io::Read,
-    sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc,
-    },
-    thread, time,
+use std::{env, fs, path::PathBuf, process, sync::atomic::Ordering};
+
+use chip8::{
+    audio::wav::WavAudio,
+    display::{display::CrossTermDisplay, Renderer},
+    fonts::FontSet,
+    keypad::keypad::CrossTermKeypad,
+    quirks::{IndexIncrement, Platform, Quirks},
+    rpl_flags::FlagStore,
+    Chip8, Chip8Options, DecodeErrorPolicy,
 };
 
-use crate::commands::command_parser::parse_command;
-use display::{display::CrossTermDisplay, Display};
-
-struct Chip8 {
-    memory: [u8; 4096],
-    display_data: [[bool; 32]; 64],
-    program_counter: u16,
-    index_register: u16,
-    stack: Vec<u16>,
-    delay_timer: u8,
-    sound_timer: u8,
-    registers: [u8; 16],
-    display_changed: bool,
-    use_old_bit_shift: bool,
-    display: Box<dyn Display>,
+const USAGE: &str = "\
+Usage: chip8 [OPTIONS] <ROM>
+
+Options:
+  --ips <N>                  Instructions per second [default: 700]
+  --cycles-per-frame <N>     Instructions per 60 Hz frame, instead of --ips
+  --seed <N>                 Seed for the random number instruction
+  --platform <NAME>          Quirk preset: vip, chip48, schip1.0, schip1.1, xochip
+  --index-increment <MODE>   How far FX55/FX65 move I: none, x, x+1
+
+Quirks, each turned off again with --no-<NAME>:
+  --vf-reset                 8XY1/8XY2/8XY3 set VF to 0
+  --display-wait             DXYN waits for the next 60 Hz frame
+  --clipping                 Sprites are clipped at the screen edge instead of wrapping
+  --shift-vy                 8XY6/8XYE shift VY into VX instead of shifting VX
+  --jump-vx                  BNNN jumps to XNN + VX instead of NNN + V0
+  --half-pixel-scroll        Scrolling moves in half pixels in low resolution
+  --collision-rows           DXYN sets VF to the number of rows that collided in high resolution
+
+Other options:
+  --font <NAME>              Font: default, vip, dream6800, eti660, fishnchips
+  --font-file <PATH>         Load an 80 byte font from a file
+  --renderer <NAME>          Renderer: blocks, half-blocks
+  --on-bad-opcode <POLICY>   What to do on an unknown instruction: halt, skip
+  --flags-dir <PATH>         Where FX75 flags are saved between sessions
+                             [default: $XDG_DATA_HOME/chip8/flags]
+  --wav <PATH>               Record the sound to a WAV file
+  --sample-rate <N>          Sample rate of the recorded sound [default: 44100]
+  -h, --help                 Print this help";
+
+struct Args {
+    rom_path: String,
+    options: Chip8Options,
+    renderer: Renderer,
+    flags_dir: Option<PathBuf>,
+    wav_path: Option<PathBuf>,
 }
 
-impl Chip8 {
-    pub fn new() -> Chip8 {
-        let display = Box::new(CrossTermDisplay::new());
-
-        let mut new_chip8 = Chip8 {
-            memory: [0; 4096],
-            display_data: [[false; 32]; 64],
-            program_counter: 0x200,
-            index_register: 0,
-            stack: Vec::new(),
-            delay_timer: 0,
-            sound_timer: 0,
-            registers: [0; 16],
-            display_changed: false,
-            use_old_bit_shift: false,
-            display,
-        };
+enum Action {
+    Run(Box<Args>),
+    Help,
+}
+
+type QuirkSwitch = fn(&mut Quirks) -> &mut bool;
+
+/// `--<name>` turns a quirk on and `--no-<name>` turns it off.
+const QUIRK_FLAGS: [(&str, QuirkSwitch); 7] = [
+    ("vf-reset", |quirks| &mut quirks.vf_reset),
+    ("display-wait", |quirks| &mut quirks.display_wait),
+    ("clipping", |quirks| &mut quirks.clipping),
+    ("shift-vy", |quirks| &mut quirks.shift_uses_vy),
+    ("jump-vx", |quirks| &mut quirks.jump_uses_vx),
+    ("half-pixel-scroll", |quirks| &mut quirks.half_pixel_scroll),
+    ("collision-rows", |quirks| &mut quirks.collision_counts_rows),
+];
+
+fn quirk_flag(arg: &str) -> Option<(QuirkSwitch, bool)> {
+    let name = arg.strip_prefix("--")?;
+    let (name, enabled) = match name.strip_prefix("no-") {
+        Some(name) => (name, false),
+        None => (name, true),
+    };
+    QUIRK_FLAGS
+        .iter()
+        .find(|(flag, _)| *flag == name)
+        .map(|(_, switch)| (*switch, enabled))
+}
 
-        new_chip8.set_defaults();
-        new_chip8
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Action, String> {
+    let mut options = Chip8Options::default();
+    let mut rom_path = None;
+    let mut platform = None;
+    let mut index_increment = None;
+    let mut renderer = Renderer::default();
+    let mut flags_dir = None;
+    let mut wav_path = None;
+    // Quirk flags override the platform preset whatever order they're given in
+    let mut quirk_overrides = Vec::new();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{} needs a value", name));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Action::Help),
+            "--ips" => {
+                options.instructions_per_second = parse_number(&arg, &value(&arg)?)?;
+            }
+            "--cycles-per-frame" => {
+                let cycles: u32 = parse_number(&arg, &value(&arg)?)?;
+                options.instructions_per_second = cycles
+                    .checked_mul(60)
+                    .ok_or_else(|| format!("{} is too large", arg))?;
+            }
+            "--seed" => options.seed = Some(parse_number(&arg, &value(&arg)?)?),
+            "--platform" => {
+                let name = value(&arg)?;
+                platform = Some(Platform::from_name(&name).ok_or_else(|| {
+                    format!(
+                        "unknown platform '{}', expected vip, chip48, schip1.0, schip1.1 or xochip",
+                        name
+                    )
+                })?);
+            }
+            "--index-increment" => {
+                let name = value(&arg)?;
+                index_increment = Some(IndexIncrement::from_name(&name).ok_or_else(|| {
+                    format!(
+                        "unknown index increment '{}', expected none, x or x+1",
+                        name
+                    )
+                })?);
+            }
+            "--font" => {
+                let name = value(&arg)?;
+                options.font_set = FontSet::from_name(&name).ok_or_else(|| {
+                    format!(
+                        "unknown font '{}', expected default, vip, dream6800, eti660 or fishnchips",
+                        name
+                    )
+                })?;
+            }
+            "--font-file" => {
+                let path = value(&arg)?;
+                let font = fs::read(&path)
+                    .map_err(|error| format!("failed to read font file '{}': {}", path, error))?;
+                let font = font.try_into().map_err(|font: Vec<u8>| {
+                    format!(
+                        "font file '{}' is {} bytes long, expected 80",
+                        path,
+                        font.len()
+                    )
+                })?;
+                options.font_set = FontSet::Custom(font);
+            }
+            "--renderer" => {
+                let name = value(&arg)?;
+                renderer = Renderer::from_name(&name).ok_or_else(|| {
+                    format!(
+                        "unknown renderer '{}', expected blocks or half-blocks",
+                        name
+                    )
+                })?;
+            }
+            "--on-bad-opcode" => {
+                options.decode_error_policy = match value(&arg)?.as_str() {
+                    "halt" => DecodeErrorPolicy::Halt,
+                    "skip" => DecodeErrorPolicy::Skip,
+                    policy => {
+                        return Err(format!(
+                            "unknown policy '{}', expected halt or skip",
+                            policy
+                        ))
+                    }
+                };
+            }
+            "--flags-dir" => flags_dir = Some(PathBuf::from(value(&arg)?)),
+            "--wav" => wav_path = Some(PathBuf::from(value(&arg)?)),
+            "--sample-rate" => options.sample_rate = parse_number(&arg, &value(&arg)?)?,
+            _ if quirk_flag(&arg).is_some() => quirk_overrides.extend(quirk_flag(&arg)),
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+            _ if rom_path.is_some() => return Err(format!("unexpected argument '{}'", arg)),
+            _ => rom_path = Some(arg),
+        }
     }
 
-    fn set_defaults(&mut self) {
-        self.set_fonts();
+    if let Some(platform) = platform {
+        options.quirks = platform.quirks();
+        options.xo_chip = platform == Platform::XoChip;
+    }
+    for (switch, enabled) in quirk_overrides {
+        *switch(&mut options.quirks) = enabled;
     }
+    if let Some(index_increment) = index_increment {
+        options.quirks.index_increment = index_increment;
+    }
+
+    let rom_path = rom_path.ok_or("no ROM given")?;
+    Ok(Action::Run(Box::new(Args {
+        rom_path,
+        options,
+        renderer,
+        flags_dir,
+        wav_path,
+    })))
+}
 
-    pub fn load_program(&mut self, program: &[u8]) {
-        let mut address = 0x200;
-        if (address + program.len()) > 4096 {
-            panic!("Program too long to fit in ram, solution not implemented")
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{}' for {}, expected a number", value, name))
+}
+
+/// `$XDG_DATA_HOME/chip8/flags`, falling back to `~/.local/share/chip8/flags`.
+fn default_flags_dir() -> Option<PathBuf> {
+    let data_home = env::var_os("XDG_DATA_HOME")
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))?;
+    Some(data_home.join("chip8").join("flags"))
+}
+
+fn main() {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(Action::Run(args)) => *args,
+        Ok(Action::Help) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            process::exit(2);
         }
+    };
 
-        for byte in program {
-            self.memory[address] = *byte;
-            address += 1;
+    let program = match fs::read(&args.rom_path) {
+        Ok(program) => program,
+        Err(error) => {
+            eprintln!("error: failed to read ROM '{}': {}", args.rom_path, error);
+            process::exit(1);
         }
-    }
+    };
 
-    pub fn start(&mut self) {
-        let target_ft = time::Duration::from_secs(1) / 700;
-        // let start = time::Instant::now();
-        let close_signal = Arc::new(AtomicBool::new(false));
-        let close_signal_in_closure = close_signal.clone();
-        ctrlc::set_handler(move || {
-            close_signal_in_closure.store(true, Ordering::SeqCst);
-        })
-        .expect("Test");
-        while !close_signal.load(Ordering::SeqCst) {
-            let now = time::Instant::now();
-            let command =
-                &self.memory[(self.program_counter as usize)..(self.program_counter as usize + 2)];
-            self.program_counter += 2;
-            let decoded_command = parse_command(command);
-            decoded_command.execute(self);
-            if self.display_changed {
-                self.display
-                    .draw_display(&self.display_data)
-                    .expect("Failed to draw display to console");
-                self.display_changed = false;
-            }
-            if let Some(i) = target_ft.checked_sub(now.elapsed()) {
-                thread::sleep(i);
-            }
+    let flag_store = args
+        .flags_dir
+        .or_else(default_flags_dir)
+        .map(FlagStore::new);
+
+    let mut options = args.options;
+    options.display = Box::new(CrossTermDisplay::with_renderer(args.renderer));
+    options.keypad = Box::new(CrossTermKeypad::new());
+    let mut emulator = Chip8::with_options(options);
+    if let Err(error) = emulator.load_program(&program) {
+        eprintln!("error: ROM '{}' can't be loaded: {}", args.rom_path, error);
+        process::exit(1);
+    }
+    if let Some(store) = &flag_store {
+        match store.load(&program) {
+            Ok(Some(flags)) => emulator.set_flags(&flags),
+            Ok(None) => {}
+            Err(error) => eprintln!("warning: failed to load saved flags: {}", error),
         }
-        self.display.close_display();
-    }
-
-    fn set_fonts(&mut self) {
-        let font = [
-            0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
-            0x20, 0x60, 0x20, 0x20, 0x70, // 1
-            0xF0, 0x10, 0xF0, 0x80, 0xF0, // 2
-            0xF0, 0x10, 0xF0, 0x10, 0xF0, // 3
-            0x90, 0x90, 0xF0, 0x10, 0x10, // 4
-            0xF0, 0x80, 0xF0, 0x10, 0xF0, // 5
-            0xF0, 0x80, 0xF0, 0x90, 0xF0, // 6
-            0xF0, 0x10, 0x20, 0x40, 0x40, // 7
-            0xF0, 0x90, 0xF0, 0x90, 0xF0, // 8
-            0xF0, 0x90, 0xF0, 0x10, 0xF0, // 9
-            0xF0, 0x90, 0xF0, 0x90, 0x90, // A
-            0xE0, 0x90, 0xE0, 0x90, 0xE0, // B
-            0xF0, 0x80, 0x80, 0x80, 0xF0, // C
-            0xE0, 0x90, 0x90, 0x90, 0xE0, // D
-            0xF0, 0x80, 0xF0, 0x80, 0xF0, // E
-            0xF0, 0x80, 0xF0, 0x80, 0x80, // F
-        ];
-
-        for (i, byte) in font.into_iter().enumerate() {
-            self.memory[0x050 + i] = byte;
+    }
+    let saved_flags = emulator.flags().to_vec();
+    if let Some(path) = &args.wav_path {
+        match WavAudio::create(path, emulator.sample_rate()) {
+            Ok(audio) => emulator.set_audio(Box::new(audio)),
+            Err(error) => {
+                eprintln!("error: failed to create '{}': {}", path.display(), error);
+                process::exit(1);
+            }
         }
     }
-}
 
-fn main() {
-    let mut program: Vec<u8> = Vec::new();
-    let mut file = File::open("roms/5-quirks.ch8").unwrap();
-    file.read_to_end(&mut program)
-        .expect("Failed to read program");
-    let mut emulator = Chip8::new();
-    emulator.load_program(&program);
-    emulator.start();
+    let stop_flag = emulator.stop_flag();
+    if let Err(error) = ctrlc::set_handler(move || stop_flag.store(true, Ordering::SeqCst)) {
+        eprintln!("warning: failed to install the Ctrl-C handler: {}", error);
+    }
+    let result = emulator.start();
+
+    if let Some(store) = &flag_store {
+        if emulator.flags() != saved_flags {
+            if let Err(error) = store.save(&program, emulator.flags()) {
+                eprintln!(
+                    "warning: failed to save flags to '{}': {}",
+                    store.directory().display(),
+                    error
+                );
+            }
+        }
+    }
+    if let Err(error) = result {
+        eprintln!("Emulator halted: {}", error);
+        process::exit(1);
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+
+    fn parse(args: &[&str]) -> Result<Action, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn parse_run(args: &[&str]) -> Args {
+        match parse(args) {
+            Ok(Action::Run(args)) => *args,
+            Ok(Action::Help) => panic!("expected run, got help"),
+            Err(error) => panic!("expected run, got error {}", error),
+        }
+    }
+
+    #[test]
+    fn test_rom_path_and_defaults() {
+        let args = parse_run(&["game.ch8"]);
+
+        assert_eq!(args.rom_path, "game.ch8");
+        assert_eq!(args.options.instructions_per_second, 700);
+        assert_eq!(args.options.seed, None);
+        assert!(!args.options.xo_chip);
+        assert_eq!(args.flags_dir, None);
+    }
+
+    #[test]
+    fn test_speed_options() {
+        let args = parse_run(&["--ips", "1000", "game.ch8"]);
+        assert_eq!(args.options.instructions_per_second, 1000);
+
+        let args = parse_run(&["game.ch8", "--cycles-per-frame", "15"]);
+        assert_eq!(args.options.instructions_per_second, 900);
+    }
+
+    #[test]
+    fn test_quirk_flags_override_platform() {
+        let args = parse_run(&["--no-shift-vy", "--platform", "vip", "game.ch8"]);
+
+        assert_eq!(
+            args.options.quirks,
+            Quirks {
+                shift_uses_vy: false,
+                ..Quirks::COSMAC_VIP
+            }
+        );
+
+        let args = parse_run(&["--platform", "schip", "--display-wait", "game.ch8"]);
+
+        assert_eq!(
+            args.options.quirks,
+            Quirks {
+                display_wait: true,
+                ..Quirks::SUPER_CHIP_1_1
+            }
+        );
+
+        let args = parse_run(&["--index-increment", "x", "--platform", "vip", "game.ch8"]);
+
+        assert_eq!(
+            args.options.quirks,
+            Quirks {
+                index_increment: IndexIncrement::X,
+                ..Quirks::COSMAC_VIP
+            }
+        );
+    }
+
+    #[test]
+    fn test_every_quirk_flag() {
+        for (name, switch) in QUIRK_FLAGS {
+            let mut quirks = Quirks::default();
+            *switch(&mut quirks) = true;
+            let args = parse_run(&[&format!("--{}", name), "game.ch8"]);
+            assert_eq!(args.options.quirks, quirks);
+
+            *switch(&mut quirks) = false;
+            let args = parse_run(&[&format!("--no-{}", name), "game.ch8"]);
+            assert_eq!(args.options.quirks, quirks);
+        }
+    }
+
+    #[test]
+    fn test_seed_and_renderer() {
+        let args = parse_run(&["--seed", "42", "--renderer", "half-blocks", "game.ch8"]);
+
+        assert_eq!(args.options.seed, Some(42));
+        assert_eq!(args.renderer, Renderer::HalfBlocks);
+    }
+
+    #[test]
+    fn test_xo_chip_platform_and_flags_dir() {
+        let args = parse_run(&["--platform", "xochip", "--flags-dir", "saves", "game.ch8"]);
+
+        assert!(args.options.xo_chip);
+        assert_eq!(args.options.quirks, Quirks::XO_CHIP);
+        assert_eq!(args.flags_dir, Some(PathBuf::from("saves")));
+    }
+
+    #[test]
+    fn test_audio_options() {
+        let args = parse_run(&["--wav", "out.wav", "--sample-rate", "8000", "game.ch8"]);
+
+        assert_eq!(args.wav_path, Some(PathBuf::from("out.wav")));
+        assert_eq!(args.options.sample_rate, 8000);
+    }
+
+    #[test]
+    fn test_help() {
+        assert!(matches!(parse(&["--help"]), Ok(Action::Help)));
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |args: &[&str]| match parse(args) {
+            Err(error) => error,
+            Ok(_) => panic!("expected an error for {:?}", args),
+        };
+
+        assert_eq!(error(&[]), "no ROM given");
+        assert_eq!(error(&["--ips"]), "--ips needs a value");
+        assert_eq!(
+            error(&["--ips", "fast", "game.ch8"]),
+            "invalid value 'fast' for --ips, expected a number"
+        );
+        assert_eq!(
+            error(&["--platform", "nes", "game.ch8"]),
+            "unknown platform 'nes', expected vip, chip48, schip1.0, schip1.1 or xochip"
+        );
+        assert_eq!(
+            error(&["--index-increment", "2", "game.ch8"]),
+            "unknown index increment '2', expected none, x or x+1"
+        );
+        assert_eq!(error(&["--turbo", "game.ch8"]), "unknown option '--turbo'");
+        assert_eq!(
+            error(&["game.ch8", "other.ch8"]),
+            "unexpected argument 'other.ch8'"
+        );
+    }
 }
@@ -0,0 +1,161 @@
+/// Behaviours that differ between CHIP-8 interpreters. ROMs written for one
+/// platform often rely on that platform's choices.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Quirks {
+    /// 8XY1, 8XY2 and 8XY3 set VF to 0.
+    pub vf_reset: bool,
+    /// How far FX55 and FX65 move I.
+    pub index_increment: IndexIncrement,
+    /// DXYN waits for the next 60 Hz vertical blank before the program carries on.
+    pub display_wait: bool,
+    /// Sprites are cut off at the edge of the screen instead of wrapping round.
+    pub clipping: bool,
+    /// 8XY6 and 8XYE shift VY into VX instead of shifting VX in place.
+    pub shift_uses_vy: bool,
+    /// BNNN is read as BXNN and jumps to XNN + VX instead of NNN + V0.
+    pub jump_uses_vx: bool,
+    /// In low resolution 00CN, 00FB and 00FC move by high resolution pixels,
+    /// half a low resolution pixel each, as SUPER-CHIP 1.1 drew low
+    /// resolution pixel-doubled on its 128x64 screen.
+    pub half_pixel_scroll: bool,
+    /// In high resolution DXYN sets VF to the number of sprite rows that
+    /// collided instead of just 1, as SUPER-CHIP 1.1 did.
+    pub collision_counts_rows: bool,
+}
+
+impl Quirks {
+    pub const COSMAC_VIP: Quirks = Quirks {
+        vf_reset: true,
+        index_increment: IndexIncrement::XPlusOne,
+        display_wait: true,
+        clipping: true,
+        shift_uses_vy: true,
+        jump_uses_vx: false,
+        half_pixel_scroll: false,
+        collision_counts_rows: false,
+    };
+
+    pub const CHIP_48: Quirks = Quirks {
+        vf_reset: false,
+        index_increment: IndexIncrement::X,
+        display_wait: false,
+        clipping: true,
+        shift_uses_vy: false,
+        jump_uses_vx: true,
+        half_pixel_scroll: false,
+        collision_counts_rows: false,
+    };
+
+    pub const SUPER_CHIP_1_0: Quirks = Quirks {
+        vf_reset: false,
+        index_increment: IndexIncrement::X,
+        display_wait: false,
+        clipping: true,
+        shift_uses_vy: false,
+        jump_uses_vx: true,
+        half_pixel_scroll: false,
+        collision_counts_rows: false,
+    };
+
+    pub const SUPER_CHIP_1_1: Quirks = Quirks {
+        vf_reset: false,
+        index_increment: IndexIncrement::None,
+        display_wait: false,
+        clipping: true,
+        shift_uses_vy: false,
+        jump_uses_vx: true,
+        half_pixel_scroll: true,
+        collision_counts_rows: true,
+    };
+
+    pub const XO_CHIP: Quirks = Quirks {
+        vf_reset: false,
+        index_increment: IndexIncrement::XPlusOne,
+        display_wait: false,
+        clipping: false,
+        shift_uses_vy: true,
+        jump_uses_vx: false,
+        half_pixel_scroll: false,
+        collision_counts_rows: false,
+    };
+}
+
+/// How far FX55 and FX65 move I after storing or reading V0 to VX.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IndexIncrement {
+    /// I is left alone, as on SUPER-CHIP 1.1.
+    None,
+    /// I moves by X, stopping on the last register, as on the CHIP-48 and
+    /// SUPER-CHIP 1.0.
+    X,
+    /// I moves by X + 1, past the last register, as on the COSMAC VIP.
+    XPlusOne,
+}
+
+impl IndexIncrement {
+    pub fn from_name(name: &str) -> Option<IndexIncrement> {
+        match name {
+            "none" => Some(IndexIncrement::None),
+            "x" => Some(IndexIncrement::X),
+            "x+1" => Some(IndexIncrement::XPlusOne),
+            _ => None,
+        }
+    }
+
+    /// How far I moves after FX55 or FX65 with register X.
+    pub fn amount(self, x: u8) -> u16 {
+        match self {
+            IndexIncrement::None => 0,
+            IndexIncrement::X => x as u16,
+            IndexIncrement::XPlusOne => x as u16 + 1,
+        }
+    }
+}
+
+impl Default for Quirks {
+    /// The behaviour this emulator had before quirks could be configured.
+    fn default() -> Self {
+        Quirks {
+            vf_reset: false,
+            index_increment: IndexIncrement::None,
+            display_wait: false,
+            clipping: true,
+            shift_uses_vy: false,
+            jump_uses_vx: false,
+            half_pixel_scroll: false,
+            collision_counts_rows: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Platform {
+    CosmacVip,
+    Chip48,
+    SuperChip10,
+    SuperChip11,
+    XoChip,
+}
+
+impl Platform {
+    pub fn from_name(name: &str) -> Option<Platform> {
+        match name {
+            "vip" | "chip8" => Some(Platform::CosmacVip),
+            "chip48" => Some(Platform::Chip48),
+            "schip1.0" => Some(Platform::SuperChip10),
+            "schip1.1" | "schip" => Some(Platform::SuperChip11),
+            "xochip" => Some(Platform::XoChip),
+            _ => None,
+        }
+    }
+
+    pub fn quirks(self) -> Quirks {
+        match self {
+            Platform::CosmacVip => Quirks::COSMAC_VIP,
+            Platform::Chip48 => Quirks::CHIP_48,
+            Platform::SuperChip10 => Quirks::SUPER_CHIP_1_0,
+            Platform::SuperChip11 => Quirks::SUPER_CHIP_1_1,
+            Platform::XoChip => Quirks::XO_CHIP,
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Saving the RPL user flags between sessions. SUPER-CHIP games keep their
+//! high scores in the flags the HP-48 calculator exposed through FX75 and
+//! FX85, so each ROM gets its own file named after a hash of its contents.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+pub const SUPER_CHIP_FLAG_COUNT: usize = 8;
+pub const MAX_FLAG_COUNT: usize = 16;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64 bit FNV-1a hash of a ROM, stable across builds and platforms.
+pub fn rom_hash(rom: &[u8]) -> u64 {
+    rom.iter().fold(FNV_OFFSET_BASIS, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// A directory of saved flags, one file per ROM.
+pub struct FlagStore {
+    directory: PathBuf,
+}
+
+impl FlagStore {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    fn path(&self, rom: &[u8]) -> PathBuf {
+        self.directory.join(format!("{:016x}.flags", rom_hash(rom)))
+    }
+
+    /// Reads the flags saved for `rom`, or `None` if it has never saved any.
+    pub fn load(&self, rom: &[u8]) -> io::Result<Option<Vec<u8>>> {
+        match fs::read(self.path(rom)) {
+            Ok(mut flags) => {
+                flags.truncate(MAX_FLAG_COUNT);
+                Ok(Some(flags))
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn save(&self, rom: &[u8], flags: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        fs::write(self.path(rom), flags)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, process};
+
+    use crate::rpl_flags::{rom_hash, FlagStore};
+
+    #[test]
+    fn test_rom_hash() {
+        // Reference values for FNV-1a 64
+        assert_eq!(rom_hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(rom_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(rom_hash(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let directory = env::temp_dir().join(format!("chip8-flags-test-{}", process::id()));
+        let store = FlagStore::new(&directory);
+        let rom = [0x00, 0xE0, 0x12, 0x00];
+
+        assert_eq!(store.load(&rom).unwrap(), None);
+
+        store.save(&rom, &[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+
+        assert_eq!(
+            store.load(&rom).unwrap(),
+            Some(vec![1, 2, 3, 4, 5, 6, 7, 8])
+        );
+        assert_eq!(store.load(&[0x00, 0xE0]).unwrap(), None);
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
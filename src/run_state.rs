@@ -0,0 +1,82 @@
+use std::{error::Error, fmt};
+
+use crate::decode_error::DecodeError;
+
+/// What the emulator is doing, as seen from outside it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RunState {
+    Running,
+    /// Stopped by `Chip8::pause`, carries on after `Chip8::resume`.
+    Paused,
+    /// Inside FX0A, waiting for a key to be pressed and released.
+    WaitingForKey,
+    Halted(HaltReason),
+}
+
+/// Why the emulator stopped running.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HaltReason {
+    /// The program ran 00FD.
+    Exit,
+    /// An instruction couldn't be decoded under `DecodeErrorPolicy::Halt`.
+    BadOpcode(DecodeError),
+    /// 2NNN at `address` with the stack already full.
+    StackOverflow { address: u16 },
+    /// 00EE at `address` with nothing on the stack to return to.
+    StackUnderflow { address: u16 },
+    /// Execution reached an address added with `Chip8::add_breakpoint`. The
+    /// instruction there hasn't run yet.
+    Breakpoint(u16),
+}
+
+impl fmt::Display for HaltReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HaltReason::Exit => write!(f, "program exited"),
+            HaltReason::BadOpcode(error) => write!(f, "{}", error),
+            HaltReason::StackOverflow { address } => {
+                write!(f, "stack overflow at address {:03X}", address)
+            }
+            HaltReason::StackUnderflow { address } => {
+                write!(f, "return with an empty stack at address {:03X}", address)
+            }
+            HaltReason::Breakpoint(address) => write!(f, "breakpoint at address {:03X}", address),
+        }
+    }
+}
+
+impl Error for HaltReason {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            HaltReason::BadOpcode(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::decode_error::DecodeError;
+    use crate::run_state::HaltReason;
+
+    #[test]
+    fn test_halt_reason_message() {
+        assert_eq!(HaltReason::Exit.to_string(), "program exited");
+        assert_eq!(
+            HaltReason::BadOpcode(DecodeError::unknown(0x2A4, &[0xE1, 0x23])).to_string(),
+            "unknown instruction E123 at address 2A4"
+        );
+        assert_eq!(
+            HaltReason::StackOverflow { address: 0x30A }.to_string(),
+            "stack overflow at address 30A"
+        );
+        assert_eq!(
+            HaltReason::StackUnderflow { address: 0x208 }.to_string(),
+            "return with an empty stack at address 208"
+        );
+        assert_eq!(
+            HaltReason::Breakpoint(0x200).to_string(),
+            "breakpoint at address 200"
+        );
+    }
+}
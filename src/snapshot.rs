@@ -0,0 +1,315 @@
+//! A plain-text picture of the screen, for tests to compare against.
+//!
+//! Each row of pixels is a line of text, `.` for a blank pixel and `#` for
+//! one lit on the first plane. XO-CHIP colours that use other planes are
+//! written as their hex digit, `2` to `F`. Leading whitespace and blank lines
+//! are ignored, so a snapshot can be written as an indented string in a test.
+//! Rows and columns left off the end are blank, a snapshot only needs to
+//! cover the part of the screen that's drawn on.
+//!
+//! Snapshots kept in files can be rewritten from what the emulator drew by
+//! running the tests with `UPDATE_SNAPSHOTS` set.
+
+use std::{env, error::Error, fmt, fs, path::Path};
+
+use crate::framebuffer::{
+    Framebuffer, HIGH_RES_HEIGHT, HIGH_RES_WIDTH, LOW_RES_HEIGHT, LOW_RES_WIDTH,
+};
+
+pub const UPDATE_VARIABLE: &str = "UPDATE_SNAPSHOTS";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ParseSnapshotError {
+    UnknownPixel {
+        row: usize,
+        column: usize,
+        pixel: char,
+    },
+    TooLarge {
+        width: usize,
+        height: usize,
+    },
+}
+
+impl fmt::Display for ParseSnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseSnapshotError::UnknownPixel { row, column, pixel } => write!(
+                f,
+                "unknown pixel {:?} at row {}, column {}",
+                pixel, row, column
+            ),
+            ParseSnapshotError::TooLarge { width, height } => write!(
+                f,
+                "snapshot is {}x{}, the largest screen is {}x{}",
+                width, height, HIGH_RES_WIDTH, HIGH_RES_HEIGHT
+            ),
+        }
+    }
+}
+
+impl Error for ParseSnapshotError {}
+
+pub fn to_snapshot(framebuffer: &Framebuffer) -> String {
+    let mut snapshot = String::with_capacity((framebuffer.width() + 1) * framebuffer.height());
+    for y in 0..framebuffer.height() {
+        for x in 0..framebuffer.width() {
+            snapshot.push(pixel_char(framebuffer.colour(x, y)));
+        }
+        snapshot.push('\n');
+    }
+    snapshot
+}
+
+/// Reads a snapshot into a low resolution framebuffer, or a high resolution
+/// one if it doesn't fit in 64x32.
+pub fn parse(snapshot: &str) -> Result<Framebuffer, ParseSnapshotError> {
+    parse_with_resolution(snapshot, false)
+}
+
+fn parse_with_resolution(
+    snapshot: &str,
+    high_res: bool,
+) -> Result<Framebuffer, ParseSnapshotError> {
+    let rows: Vec<&str> = snapshot
+        .lines()
+        .map(str::trim)
+        .filter(|row| !row.is_empty())
+        .collect();
+    let width = rows
+        .iter()
+        .map(|row| row.chars().count())
+        .max()
+        .unwrap_or(0);
+    let height = rows.len();
+    if width > HIGH_RES_WIDTH || height > HIGH_RES_HEIGHT {
+        return Err(ParseSnapshotError::TooLarge { width, height });
+    }
+
+    let mut framebuffer = Framebuffer::new();
+    framebuffer.set_high_res(high_res || width > LOW_RES_WIDTH || height > LOW_RES_HEIGHT);
+    for (y, row) in rows.iter().enumerate() {
+        for (x, pixel) in row.chars().enumerate() {
+            let colour = match pixel {
+                '.' => 0,
+                '#' => 1,
+                _ => match pixel.to_digit(16) {
+                    Some(colour) if colour >= 2 => colour as u8,
+                    _ => {
+                        return Err(ParseSnapshotError::UnknownPixel {
+                            row: y,
+                            column: x,
+                            pixel,
+                        })
+                    }
+                },
+            };
+            framebuffer.set_colour(x, y, colour);
+        }
+    }
+    Ok(framebuffer)
+}
+
+fn pixel_char(colour: u8) -> char {
+    match colour {
+        0 => '.',
+        1 => '#',
+        _ => char::from_digit(colour as u32, 16)
+            .unwrap()
+            .to_ascii_uppercase(),
+    }
+}
+
+/// Describes how `actual` differs from `expected`, or `None` if they match.
+///
+/// Pixels that only `actual` has lit are marked `+`, ones it's missing `-`,
+/// and ones lit in a different colour `*`. The full snapshot of `actual`
+/// follows so it can be copied into the test once it's been checked.
+pub fn diff(expected: &Framebuffer, actual: &Framebuffer) -> Option<String> {
+    if (expected.width(), expected.height()) != (actual.width(), actual.height()) {
+        return Some(format!(
+            "expected a {}x{} screen, found {}x{}\n\n{}",
+            expected.width(),
+            expected.height(),
+            actual.width(),
+            actual.height(),
+            to_snapshot(actual)
+        ));
+    }
+
+    let mut grid = String::new();
+    let mut differences = 0;
+    for y in 0..actual.height() {
+        for x in 0..actual.width() {
+            let (expected, actual) = (expected.colour(x, y), actual.colour(x, y));
+            grid.push(if expected == actual {
+                pixel_char(actual)
+            } else {
+                differences += 1;
+                match (expected, actual) {
+                    (0, _) => '+',
+                    (_, 0) => '-',
+                    _ => '*',
+                }
+            });
+        }
+        grid.push('\n');
+    }
+    if differences == 0 {
+        return None;
+    }
+    Some(format!(
+        "{} {}, + lit but expected blank, - blank but expected lit, * wrong colour\n\n\
+         {}\nactual:\n\n{}",
+        differences,
+        if differences == 1 {
+            "pixel differs"
+        } else {
+            "pixels differ"
+        },
+        grid,
+        to_snapshot(actual)
+    ))
+}
+
+/// Panics with a visual diff unless `actual` matches the `expected` snapshot.
+///
+/// A snapshot smaller than 64x32 is compared at `actual`'s resolution, so a
+/// high resolution test only needs to draw the corner it cares about.
+#[track_caller]
+pub fn assert_framebuffer_eq(actual: &Framebuffer, expected: &str) {
+    let expected = match parse_with_resolution(expected, actual.is_high_res()) {
+        Ok(expected) => expected,
+        Err(error) => panic!("bad snapshot: {}", error),
+    };
+    if let Some(diff) = diff(&expected, actual) {
+        panic!("framebuffer doesn't match the snapshot, {}", diff);
+    }
+}
+
+/// Compares `actual` with the snapshot stored at `path`. With
+/// `UPDATE_SNAPSHOTS` set the file is written from `actual` instead.
+pub fn check_snapshot_file(actual: &Framebuffer, path: &Path) -> Result<(), String> {
+    if env::var_os(UPDATE_VARIABLE).is_some() {
+        return fs::write(path, to_snapshot(actual))
+            .map_err(|error| format!("couldn't write {}: {}", path.display(), error));
+    }
+
+    let snapshot = fs::read_to_string(path).map_err(|error| {
+        format!(
+            "couldn't read {}: {}, run with {} set to create it",
+            path.display(),
+            error,
+            UPDATE_VARIABLE
+        )
+    })?;
+    let expected = parse_with_resolution(&snapshot, actual.is_high_res())
+        .map_err(|error| format!("bad snapshot {}: {}", path.display(), error))?;
+    match diff(&expected, actual) {
+        None => Ok(()),
+        Some(diff) => Err(format!(
+            "framebuffer doesn't match {}, {}",
+            path.display(),
+            diff
+        )),
+    }
+}
+
+#[track_caller]
+pub fn assert_snapshot_file(actual: &Framebuffer, path: &Path) {
+    if let Err(error) = check_snapshot_file(actual, path) {
+        panic!("{}", error);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::framebuffer::Framebuffer;
+    use crate::snapshot::{assert_framebuffer_eq, diff, parse, to_snapshot, ParseSnapshotError};
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut framebuffer = Framebuffer::new();
+        framebuffer.set(0, 0, true);
+        framebuffer.set_colour(63, 31, 0xA);
+
+        let snapshot = to_snapshot(&framebuffer);
+
+        assert_eq!(snapshot.lines().count(), 32);
+        assert!(snapshot.starts_with("#....."));
+        assert!(snapshot.ends_with(".....A\n"));
+        assert_eq!(parse(&snapshot), Ok(framebuffer));
+    }
+
+    #[test]
+    fn test_parse_pads_short_snapshot() {
+        let framebuffer = parse(
+            "
+            .#
+            #.#
+            ",
+        )
+        .unwrap();
+
+        assert!(!framebuffer.is_high_res());
+        assert!(framebuffer.get(1, 0));
+        assert!(framebuffer.get(0, 1));
+        assert!(framebuffer.get(2, 1));
+        assert_eq!(to_snapshot(&framebuffer).matches('#').count(), 3);
+    }
+
+    #[test]
+    fn test_parse_high_res() {
+        let framebuffer = parse(&format!("{}#", ".".repeat(64))).unwrap();
+
+        assert!(framebuffer.is_high_res());
+        assert!(framebuffer.get(64, 0));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse("..\n.x"),
+            Err(ParseSnapshotError::UnknownPixel {
+                row: 1,
+                column: 1,
+                pixel: 'x'
+            })
+        );
+        assert_eq!(
+            parse(&".".repeat(129)),
+            Err(ParseSnapshotError::TooLarge {
+                width: 129,
+                height: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_diff_marks_differences() {
+        let expected = parse("##.\n.3.").unwrap();
+        let actual = parse("#.#\n.2.").unwrap();
+
+        let diff = diff(&expected, &actual).unwrap();
+
+        assert!(diff.starts_with("3 pixels differ"));
+        assert!(diff.contains("\n#-+....."));
+        assert!(diff.contains("\n.*......"));
+        assert_eq!(super::diff(&actual, &actual), None);
+    }
+
+    #[test]
+    fn test_assert_framebuffer_eq_at_high_res() {
+        let mut framebuffer = Framebuffer::new();
+        framebuffer.set_high_res(true);
+        framebuffer.set(1, 1, true);
+
+        assert_framebuffer_eq(&framebuffer, "..\n.#");
+    }
+
+    #[test]
+    #[should_panic(expected = "1 pixel differs")]
+    fn test_assert_framebuffer_eq_mismatch() {
+        assert_framebuffer_eq(&Framebuffer::new(), "#");
+    }
+}
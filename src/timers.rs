@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+pub(crate) const TIMER_FREQUENCY: u32 = 60;
+
+const NANOS_PER_SECOND: u128 = 1_000_000_000;
+
+/// Converts elapsed time into 60 Hz timer ticks.
+///
+/// Time is fed in explicitly rather than read from the system clock, so the
+/// timers only move when the caller says so. The emulator loop passes in the
+/// real time between instructions, tests can pass in whatever they like.
+pub(crate) struct TimerClock {
+    // Elapsed nanoseconds scaled by the timer frequency, so no rounding error
+    // builds up between ticks.
+    scaled_nanos: u128,
+}
+
+impl TimerClock {
+    pub(crate) fn new() -> Self {
+        Self { scaled_nanos: 0 }
+    }
+
+    /// Advances the clock and returns how many timer ticks are now due.
+    pub(crate) fn advance(&mut self, elapsed: Duration) -> u32 {
+        self.scaled_nanos += elapsed.as_nanos() * TIMER_FREQUENCY as u128;
+        let ticks = self.scaled_nanos / NANOS_PER_SECOND;
+        self.scaled_nanos %= NANOS_PER_SECOND;
+        ticks.try_into().unwrap_or(u32::MAX)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::timers::TimerClock;
+
+    #[test]
+    fn test_one_second_is_sixty_ticks() {
+        let mut clock = TimerClock::new();
+
+        assert_eq!(clock.advance(Duration::from_secs(1)), 60);
+    }
+
+    #[test]
+    fn test_partial_ticks_carry_over() {
+        let mut clock = TimerClock::new();
+
+        assert_eq!(clock.advance(Duration::from_millis(10)), 0);
+        assert_eq!(clock.advance(Duration::from_millis(10)), 1);
+        assert_eq!(clock.advance(Duration::from_millis(10)), 0);
+        assert_eq!(clock.advance(Duration::from_millis(4)), 1);
+    }
+
+    #[test]
+    fn test_ticks_independent_of_step_size() {
+        let mut clock = TimerClock::new();
+        let instruction_time = Duration::from_secs(1) / 500;
+
+        let ticks: u32 = (0..500 * 10).map(|_| clock.advance(instruction_time)).sum();
+
+        assert_eq!(ticks, 600);
+    }
+}
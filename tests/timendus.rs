@@ -0,0 +1,335 @@
+//! Runs the Timendus CHIP-8 test suite (https://github.com/Timendus/chip8-test-suite)
+//! headless and compares the screen each ROM ends on with a `#`/`.` snapshot in
+//! `tests/golden`.
+//!
+//! The ROMs aren't part of this repository, so the suite is ignored by default.
+//! Put the suite's `bin/*.ch8` files in `tests/roms`, or point `CHIP8_TEST_ROMS`
+//! at a directory holding them, and run `cargo test --test timendus -- --ignored`.
+//! A missing ROM or snapshot fails its case. Run with `UPDATE_SNAPSHOTS` set to
+//! write the snapshots from the frames the ROMs end on, then check them by eye.
+
+use std::{
+    env, fmt, fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use chip8::{
+    framebuffer::Framebuffer,
+    quirks::Platform,
+    run_state::RunState,
+    snapshot::{self, check_snapshot_file},
+    Chip8, Chip8Options,
+};
+
+struct Case {
+    name: &'static str,
+    rom: &'static str,
+    platform: Platform,
+    /// The quirks, keypad and scrolling ROMs skip their menu when a choice
+    /// is already in memory at 1FF.
+    menu_choice: Option<u8>,
+    frames: u32,
+    /// Keys pressed or released before the given frame runs.
+    keys: &'static [KeyEvent],
+    /// Sub-tests the ROM reports on their own line, checked one by one.
+    lines: &'static [Line],
+}
+
+/// A sub-test and the rows of the low resolution screen its result is shown on.
+struct Line {
+    name: &'static str,
+    rows: Range<usize>,
+}
+
+struct KeyEvent {
+    frame: u32,
+    key: u8,
+    pressed: bool,
+}
+
+const fn press(frame: u32, key: u8) -> KeyEvent {
+    KeyEvent {
+        frame,
+        key,
+        pressed: true,
+    }
+}
+
+const fn release(frame: u32, key: u8) -> KeyEvent {
+    KeyEvent {
+        frame,
+        key,
+        pressed: false,
+    }
+}
+
+const MENU_CHOICE_ADDRESS: u16 = 0x1FF;
+
+/// The quirks ROM prints each quirk's name and a tick or cross on its own
+/// line.
+const QUIRK_LINES: &[Line] = &[
+    Line {
+        name: "vF reset",
+        rows: 0..5,
+    },
+    Line {
+        name: "memory",
+        rows: 5..10,
+    },
+    Line {
+        name: "display wait",
+        rows: 10..15,
+    },
+    Line {
+        name: "clipping",
+        rows: 15..20,
+    },
+    Line {
+        name: "shifting",
+        rows: 20..25,
+    },
+    Line {
+        name: "jumping",
+        rows: 25..32,
+    },
+];
+
+const CASES: &[Case] = &[
+    Case {
+        name: "1-chip8-logo",
+        rom: "1-chip8-logo.ch8",
+        platform: Platform::CosmacVip,
+        menu_choice: None,
+        frames: 60,
+        keys: &[],
+        lines: &[],
+    },
+    Case {
+        name: "2-ibm-logo",
+        rom: "2-ibm-logo.ch8",
+        platform: Platform::CosmacVip,
+        menu_choice: None,
+        frames: 60,
+        keys: &[],
+        lines: &[],
+    },
+    Case {
+        name: "3-corax+",
+        rom: "3-corax+.ch8",
+        platform: Platform::CosmacVip,
+        menu_choice: None,
+        frames: 60,
+        keys: &[],
+        lines: &[],
+    },
+    Case {
+        name: "4-flags",
+        rom: "4-flags.ch8",
+        platform: Platform::CosmacVip,
+        menu_choice: None,
+        frames: 120,
+        keys: &[],
+        lines: &[],
+    },
+    Case {
+        name: "5-quirks-chip8",
+        rom: "5-quirks.ch8",
+        platform: Platform::CosmacVip,
+        menu_choice: Some(1),
+        frames: 600,
+        keys: &[],
+        lines: QUIRK_LINES,
+    },
+    Case {
+        name: "5-quirks-schip-legacy",
+        rom: "5-quirks.ch8",
+        platform: Platform::SuperChip11,
+        menu_choice: Some(4),
+        frames: 600,
+        keys: &[],
+        lines: QUIRK_LINES,
+    },
+    Case {
+        name: "5-quirks-xochip",
+        rom: "5-quirks.ch8",
+        platform: Platform::XoChip,
+        menu_choice: Some(3),
+        frames: 600,
+        keys: &[],
+        lines: QUIRK_LINES,
+    },
+    Case {
+        name: "6-keypad-ex9e",
+        rom: "6-keypad.ch8",
+        platform: Platform::CosmacVip,
+        menu_choice: Some(1),
+        frames: 90,
+        keys: &[press(30, 0xA)],
+        lines: &[],
+    },
+    Case {
+        name: "6-keypad-exa1",
+        rom: "6-keypad.ch8",
+        platform: Platform::CosmacVip,
+        menu_choice: Some(2),
+        frames: 90,
+        keys: &[press(30, 0x3)],
+        lines: &[],
+    },
+    Case {
+        name: "6-keypad-fx0a",
+        rom: "6-keypad.ch8",
+        platform: Platform::CosmacVip,
+        menu_choice: Some(3),
+        frames: 90,
+        keys: &[press(30, 0x5), release(40, 0x5)],
+        lines: &[],
+    },
+    Case {
+        name: "8-scrolling-schip-lores",
+        rom: "8-scrolling.ch8",
+        platform: Platform::SuperChip11,
+        menu_choice: Some(1),
+        frames: 120,
+        keys: &[],
+        lines: &[],
+    },
+    Case {
+        name: "8-scrolling-schip-hires",
+        rom: "8-scrolling.ch8",
+        platform: Platform::SuperChip11,
+        menu_choice: Some(2),
+        frames: 120,
+        keys: &[],
+        lines: &[],
+    },
+    Case {
+        name: "8-scrolling-xochip-lores",
+        rom: "8-scrolling.ch8",
+        platform: Platform::XoChip,
+        menu_choice: Some(3),
+        frames: 120,
+        keys: &[],
+        lines: &[],
+    },
+    Case {
+        name: "8-scrolling-xochip-hires",
+        rom: "8-scrolling.ch8",
+        platform: Platform::XoChip,
+        menu_choice: Some(4),
+        frames: 120,
+        keys: &[],
+        lines: &[],
+    },
+];
+
+enum Outcome {
+    Pass,
+    Fail(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Pass => write!(f, "pass"),
+            Outcome::Fail(reason) => write!(f, "FAIL, {}", reason),
+        }
+    }
+}
+
+fn rom_dir() -> PathBuf {
+    env::var_os("CHIP8_TEST_ROMS")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/roms"))
+}
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+fn run(case: &Case, rom: &[u8]) -> Result<Framebuffer, String> {
+    let mut emulator = Chip8::with_options(Chip8Options {
+        seed: Some(0),
+        instructions_per_second: 1000 * 60,
+        quirks: case.platform.quirks(),
+        xo_chip: case.platform == Platform::XoChip,
+        ..Default::default()
+    });
+    emulator
+        .load_program(rom)
+        .map_err(|error| error.to_string())?;
+    if let Some(choice) = case.menu_choice {
+        emulator.write_memory(MENU_CHOICE_ADDRESS, &[choice]);
+    }
+
+    for frame in 0..case.frames {
+        for event in case.keys.iter().filter(|event| event.frame == frame) {
+            emulator.set_key(event.key, event.pressed);
+        }
+        emulator.run_frame().map_err(|error| error.to_string())?;
+        if let RunState::Halted(reason) = emulator.run_state() {
+            return Err(format!("halted on frame {}: {}", frame, reason));
+        }
+    }
+    Ok(emulator.framebuffer().clone())
+}
+
+fn check(case: &Case) -> Outcome {
+    let rom_path = rom_dir().join(case.rom);
+    let rom = match fs::read(&rom_path) {
+        Ok(rom) => rom,
+        Err(error) => {
+            return Outcome::Fail(format!("couldn't read {}: {}", rom_path.display(), error))
+        }
+    };
+    let frame = match run(case, &rom) {
+        Ok(frame) => frame,
+        Err(error) => return Outcome::Fail(error),
+    };
+
+    let golden_path = golden_dir().join(format!("{}.txt", case.name));
+    match check_snapshot_file(&frame, &golden_path) {
+        Ok(()) => Outcome::Pass,
+        Err(error) => match wrong_lines(case, &frame, &golden_path) {
+            wrong if wrong.is_empty() => Outcome::Fail(error),
+            wrong => Outcome::Fail(format!("wrong: {}, {}", wrong.join(", "), error)),
+        },
+    }
+}
+
+/// Names the lines of `case` whose pixels differ from the snapshot at `path`.
+fn wrong_lines(case: &Case, actual: &Framebuffer, path: &Path) -> Vec<&'static str> {
+    let Some(expected) = fs::read_to_string(path)
+        .ok()
+        .and_then(|snapshot| snapshot::parse(&snapshot).ok())
+    else {
+        return Vec::new();
+    };
+    if expected.is_high_res() != actual.is_high_res() {
+        return case.lines.iter().map(|line| line.name).collect();
+    }
+    let scale = actual.height() / 32;
+    case.lines
+        .iter()
+        .filter(|line| {
+            (line.rows.start * scale..line.rows.end * scale)
+                .any(|y| (0..actual.width()).any(|x| expected.colour(x, y) != actual.colour(x, y)))
+        })
+        .map(|line| line.name)
+        .collect()
+}
+
+#[test]
+#[ignore = "needs the Timendus ROMs, see the module docs"]
+fn timendus_suite() {
+    let mut failures = Vec::new();
+    for case in CASES {
+        let outcome = check(case);
+        println!("{:<26} {}", case.name, outcome);
+        if let Outcome::Fail(_) = outcome {
+            failures.push(case.name);
+        }
+    }
+    assert!(failures.is_empty(), "failed: {}", failures.join(", "));
+}